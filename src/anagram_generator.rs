@@ -0,0 +1,323 @@
+use super::*;
+
+#[cfg(feature = "graphemes")]
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Generates the distinct anagrams of a word, in lexicographic order.
+///
+/// Repeated letters are deduplicated, e.g. "aab" has 3 anagrams, not 6.
+/// Without repeated letters, the index of an anagram equals the index of the corresponding permutation of the sorted letters.
+#[derive(Clone, Debug)]
+pub struct AnagramGenerator<'a, R: Rank> {
+    units: Vec<&'a str>,
+    counts: Vec<u8>,
+    nb_units: u8,
+    nb_anagrams: R,
+    next_idx: R,
+    split: fn(&str) -> Vec<&str>,
+}
+
+pub type AnagramGenerator8<'a> = AnagramGenerator<'a, u16>;
+pub type AnagramGenerator16<'a> = AnagramGenerator<'a, u64>;
+pub type AnagramGenerator32<'a> = AnagramGenerator<'a, u128>;
+
+impl<'a, R: Rank> AnagramGenerator<'a, R> {
+    /// Permutes the `char`s of `word`.
+    pub fn new(word: &'a str) -> PResult<Self> {
+        Self::with_split(word, split_chars)
+    }
+
+    /// Permutes the extended grapheme clusters of `word`.
+    #[cfg(feature = "graphemes")]
+    pub fn graphemes(word: &'a str) -> PResult<Self> {
+        Self::with_split(word, split_graphemes)
+    }
+
+    pub fn next_anagram(&mut self) -> Option<String> {
+        self.nth(R::ZERO)
+    }
+
+    pub fn nth(&mut self, step: R) -> Option<String> {
+        let mut buf = String::new();
+        self.nth_into(step, &mut buf).then_some(buf)
+    }
+
+    /// Like `nth`, but writes the anagram into `buf`, which is cleared first.
+    ///
+    /// Returns `false`, leaving `buf` untouched, if there are no anagrams left.
+    pub fn nth_into(&mut self, step: R, buf: &mut String) -> bool {
+        let step_result = self.next_idx.saturating_add(step);
+        self.next_idx = step_result.saturating_add(R::ONE);
+        self.anagram_into(step_result, buf)
+    }
+
+    /// The `idx`-th anagram of the `char`s of `word`.
+    pub fn nth_absolute(word: &str, idx: R) -> PResult<Option<String>> {
+        AnagramGenerator::<R>::new(word).map(|ag| ag.anagram(idx))
+    }
+
+    /// The `idx`-th anagram, regardless of the progress of the generator.
+    pub fn anagram(&self, idx: R) -> Option<String> {
+        let mut buf = String::new();
+        self.anagram_into(idx, &mut buf).then_some(buf)
+    }
+
+    /// Like `anagram`, but writes into `buf`, which is cleared first.
+    ///
+    /// Returns `false`, leaving `buf` untouched, if `idx` is out of range.
+    pub fn anagram_into(&self, mut idx: R, buf: &mut String) -> bool {
+        if idx >= self.nb_anagrams {
+            return false;
+        }
+        buf.clear();
+        let mut counts = [0; 32];
+        counts[..self.counts.len()].copy_from_slice(&self.counts);
+        let mut nb_anagrams = self.nb_anagrams;
+        for nb_remaining in (1..=self.nb_units).rev() {
+            for (unit, count) in self.units.iter().zip(counts.iter_mut()) {
+                if *count == 0 {
+                    continue;
+                }
+                let nb_starting_with = mul_div(nb_anagrams, *count, nb_remaining);
+                if idx < nb_starting_with {
+                    buf.push_str(unit);
+                    *count -= 1;
+                    nb_anagrams = nb_starting_with;
+                    break;
+                }
+                idx -= nb_starting_with;
+            }
+        }
+        true
+    }
+
+    /// The index of `anagram`, split the same way as the word of this generator.
+    ///
+    /// Like `nb_anagrams`, an empty word has no anagrams.
+    pub fn rank(&self, anagram: &str) -> PResult<R> {
+        let units = (self.split)(anagram);
+        if units.len() != self.nb_units as usize {
+            return Err(PermutationGeneratorError::LengthMismatch);
+        }
+        if self.nb_units == 0 {
+            return Err(PermutationGeneratorError::NotAPermutation);
+        }
+        let mut counts = [0; 32];
+        counts[..self.counts.len()].copy_from_slice(&self.counts);
+        let mut nb_anagrams = self.nb_anagrams;
+        let mut rank = R::ZERO;
+        for (unit, nb_remaining) in units.into_iter().zip((1..=self.nb_units).rev()) {
+            let pos = match self.units.binary_search(&unit) {
+                Ok(pos) if counts[pos] > 0 => pos,
+                _ => return Err(PermutationGeneratorError::NotAPermutation),
+            };
+            for &count in counts[..pos].iter().filter(|&&count| count > 0) {
+                rank += mul_div(nb_anagrams, count, nb_remaining);
+            }
+            nb_anagrams = mul_div(nb_anagrams, counts[pos], nb_remaining);
+            counts[pos] -= 1;
+        }
+        Ok(rank)
+    }
+
+    /// The number of distinct anagrams, regardless of the progress of the generator.
+    pub fn nb_anagrams(&self) -> R {
+        self.nb_anagrams
+    }
+
+    /// Panics if the number of remaining anagrams overflows `usize`
+    pub fn nb_remaining(&self) -> usize {
+        if self.next_idx >= self.nb_anagrams {
+            return 0;
+        }
+        match (self.nb_anagrams - self.next_idx).to_usize() {
+            Some(nb) => nb,
+            None => panic!("The size of the iterator overflowed usize"),
+        }
+    }
+
+    fn with_split(word: &'a str, split: fn(&str) -> Vec<&str>) -> PResult<Self> {
+        let mut all_units = split(word);
+        if all_units.len() > R::MAX_ELEMENTS as usize {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        all_units.sort_unstable();
+        let nb_units = all_units.len() as u8;
+        let mut units: Vec<&str> = Vec::new();
+        let mut counts = Vec::new();
+        let mut nb_anagrams = if nb_units == 0 { R::ZERO } else { R::ONE };
+        for (placed, unit) in (1..=nb_units).zip(all_units) {
+            if units.last() == Some(&unit) {
+                *counts.last_mut().unwrap() += 1;
+            } else {
+                units.push(unit);
+                counts.push(1);
+            }
+            nb_anagrams = mul_div(nb_anagrams, placed, *counts.last().unwrap());
+        }
+        Ok(Self {
+            units,
+            counts,
+            nb_units,
+            nb_anagrams,
+            next_idx: R::ZERO,
+            split,
+        })
+    }
+}
+
+impl<'a, R: Rank> Iterator for AnagramGenerator<'a, R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_anagram()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let nb_remaining = self.nb_remaining();
+        (nb_remaining, Some(nb_remaining))
+    }
+
+    fn count(self) -> usize {
+        self.nb_remaining()
+    }
+}
+
+fn split_chars(word: &str) -> Vec<&str> {
+    word.char_indices()
+        .map(|(i, c)| &word[i..i + c.len_utf8()])
+        .collect()
+}
+
+#[cfg(feature = "graphemes")]
+fn split_graphemes(word: &str) -> Vec<&str> {
+    word.graphemes(true).collect()
+}
+
+/// `value * mul / div`, without overflowing when the exact result fits.
+///
+/// The caller guarantees that `div` divides `value * mul`.
+#[inline]
+fn mul_div<R: Rank>(value: R, mul: u8, div: u8) -> R {
+    let gcd = gcd(mul, div);
+    value / R::from(div / gcd) * R::from(mul / gcd)
+}
+
+#[inline]
+fn gcd(mut a: u8, mut b: u8) -> u8 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        assert_eq!(3, AnagramGenerator8::new("aab").unwrap().nb_anagrams());
+        assert_eq!(
+            34650,
+            AnagramGenerator16::new("mississippi")
+                .unwrap()
+                .nb_anagrams()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            AnagramGenerator8::new("mississippi").unwrap_err()
+        );
+        assert_eq!(0, AnagramGenerator8::new("").unwrap().count());
+    }
+
+    #[test]
+    fn iter() {
+        let anagrams = AnagramGenerator8::new("baa").unwrap().collect::<Vec<_>>();
+        assert_eq!(vec!["aab", "aba", "baa"], anagrams);
+
+        let anagrams = AnagramGenerator8::new("été").unwrap().collect::<Vec<_>>();
+        assert_eq!(vec!["téé", "été", "éét"], anagrams);
+
+        let ag = AnagramGenerator16::new("mississippi").unwrap();
+        assert_eq!(34650, ag.count());
+    }
+
+    #[test]
+    fn nth() {
+        let mut ag = AnagramGenerator8::new("abca").unwrap();
+        assert_eq!(Some("aabc".to_string()), ag.next_anagram());
+        assert_eq!(Some("acab".to_string()), ag.nth(3));
+        assert_eq!(7, ag.nb_remaining());
+        assert_eq!(Some("cbaa".to_string()), ag.nth(6));
+        assert_eq!(None, ag.next_anagram());
+
+        let mut ag = AnagramGenerator8::new("abca").unwrap();
+        let mut buf = String::from("left over");
+        assert!(ag.nth_into(1, &mut buf));
+        assert_eq!("aacb", buf);
+        assert!(!ag.nth_into(20, &mut buf));
+        assert_eq!("aacb", buf);
+    }
+
+    #[test]
+    fn nth_absolute() {
+        for idx in 0..24 {
+            let perm = PermutationGenerator8::nth_absolute(4, idx)
+                .unwrap()
+                .unwrap()
+                .map(|i| (b'a' + i) as char)
+                .collect::<String>();
+            assert_eq!(
+                Some(perm),
+                AnagramGenerator8::nth_absolute("dbca", idx).unwrap()
+            );
+        }
+        assert_eq!(
+            Some("ssssppmiiii".to_string()),
+            AnagramGenerator16::nth_absolute("mississippi", 34649).unwrap()
+        );
+        assert_eq!(
+            None,
+            AnagramGenerator16::nth_absolute("mississippi", 34650).unwrap()
+        );
+    }
+
+    #[test]
+    fn rank() {
+        let ag = AnagramGenerator16::new("mississippi").unwrap();
+        for idx in (0..34650).step_by(97) {
+            assert_eq!(Ok(idx), ag.rank(&ag.anagram(idx).unwrap()));
+        }
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            ag.rank("mississippa")
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::LengthMismatch),
+            ag.rank("mississipp")
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            AnagramGenerator16::new("").unwrap().rank("")
+        );
+    }
+
+    #[cfg(feature = "graphemes")]
+    #[test]
+    fn graphemes() {
+        let word = "ae\u{301}e\u{301}";
+        assert_eq!(30, AnagramGenerator8::new(word).unwrap().nb_anagrams());
+        let anagrams = AnagramGenerator8::graphemes(word)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "ae\u{301}e\u{301}",
+                "e\u{301}ae\u{301}",
+                "e\u{301}e\u{301}a"
+            ],
+            anagrams
+        );
+    }
+}
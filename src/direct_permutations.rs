@@ -0,0 +1,259 @@
+use super::*;
+
+/// Random access to the permutations of a slice, on the narrowest tier fitting its length.
+///
+/// Permutations are ordered lexicographically on the positions of the elements, so index `0` is the slice itself.
+pub trait DirectPermutations {
+    type Elem;
+
+    /// References to the elements of the `idx`-th permutation, `None` if `idx` is out of range.
+    fn permutation_nth(&self, idx: u128) -> PResult<Option<PermutationRefs<'_, Self::Elem>>>;
+
+    fn permutations_direct(&self) -> PResult<PermutationsDirect<'_, Self::Elem>>;
+
+    /// The number of permutations of the slice, duplicates included.
+    fn permutation_count(&self) -> PResult<u128>;
+
+    /// The index of `other` among the permutations of the slice, none for an empty slice.
+    ///
+    /// Repeated elements are matched to the earliest position not matched yet.
+    fn rank_of(&self, other: &[Self::Elem]) -> PResult<u128>
+    where
+        Self::Elem: PartialEq;
+}
+
+/// The permutations of a slice, see `DirectPermutations::permutations_direct`.
+#[derive(Clone, Debug)]
+pub struct PermutationsDirect<'a, T> {
+    pg: AutoPermutationGenerator,
+    elems: &'a [T],
+}
+
+/// A single permutation of a slice, yielding references to its elements.
+#[derive(Clone, Debug)]
+pub struct PermutationRefs<'a, T> {
+    perm: AutoPermutation,
+    elems: &'a [T],
+}
+
+impl<T> DirectPermutations for [T] {
+    type Elem = T;
+
+    fn permutation_nth(&self, idx: u128) -> PResult<Option<PermutationRefs<'_, T>>> {
+        AutoPermutationGenerator::nth_absolute(nb_elems(self)?, idx)
+            .map(|res| res.map(|perm| PermutationRefs { perm, elems: self }))
+    }
+
+    fn permutations_direct(&self) -> PResult<PermutationsDirect<'_, T>> {
+        AutoPermutationGenerator::new(nb_elems(self)?)
+            .map(|pg| PermutationsDirect { pg, elems: self })
+    }
+
+    fn permutation_count(&self) -> PResult<u128> {
+        nb_elems(self).map(factorial128)
+    }
+
+    fn rank_of(&self, other: &[T]) -> PResult<u128>
+    where
+        T: PartialEq,
+    {
+        let nb_elems = nb_elems(self)?;
+        if other.len() != self.len() {
+            return Err(PermutationGeneratorError::LengthMismatch);
+        }
+        if nb_elems == 0 {
+            return Err(PermutationGeneratorError::NotAPermutation);
+        }
+        let mut matched = 0u64;
+        let mut positions = [0; 32];
+        for (position, elem) in positions.iter_mut().zip(other) {
+            *position = (0..nb_elems)
+                .find(|&i| matched & (1 << i) == 0 && self[i as usize] == *elem)
+                .ok_or(PermutationGeneratorError::NotAPermutation)?;
+            matched |= 1 << *position;
+        }
+        lehmer_rank(nb_elems, positions[..other.len()].iter().copied())
+    }
+}
+
+impl<T> DirectPermutations for Vec<T> {
+    type Elem = T;
+
+    fn permutation_nth(&self, idx: u128) -> PResult<Option<PermutationRefs<'_, T>>> {
+        self.as_slice().permutation_nth(idx)
+    }
+
+    fn permutations_direct(&self) -> PResult<PermutationsDirect<'_, T>> {
+        self.as_slice().permutations_direct()
+    }
+
+    fn permutation_count(&self) -> PResult<u128> {
+        self.as_slice().permutation_count()
+    }
+
+    fn rank_of(&self, other: &[T]) -> PResult<u128>
+    where
+        T: PartialEq,
+    {
+        self.as_slice().rank_of(other)
+    }
+}
+
+impl<T, const N: usize> DirectPermutations for [T; N] {
+    type Elem = T;
+
+    fn permutation_nth(&self, idx: u128) -> PResult<Option<PermutationRefs<'_, T>>> {
+        self.as_slice().permutation_nth(idx)
+    }
+
+    fn permutations_direct(&self) -> PResult<PermutationsDirect<'_, T>> {
+        self.as_slice().permutations_direct()
+    }
+
+    fn permutation_count(&self) -> PResult<u128> {
+        self.as_slice().permutation_count()
+    }
+
+    fn rank_of(&self, other: &[T]) -> PResult<u128>
+    where
+        T: PartialEq,
+    {
+        self.as_slice().rank_of(other)
+    }
+}
+
+#[inline]
+fn nb_elems<T>(elems: &[T]) -> PResult<u8> {
+    match u8::try_from(elems.len()) {
+        Ok(nb_elems) if nb_elems <= <u128 as Rank>::MAX_ELEMENTS => Ok(nb_elems),
+        _ => Err(PermutationGeneratorError::TooManyElements),
+    }
+}
+
+impl<'a, T> PermutationsDirect<'a, T> {
+    pub fn nth_permutation(&mut self, step: u128) -> Option<PermutationRefs<'a, T>> {
+        let elems = self.elems;
+        self.pg
+            .nth(step)
+            .map(|perm| PermutationRefs { perm, elems })
+    }
+
+    /// Panics if the number of remaining permutations overflows `usize`, e.g. on more than 20 elements
+    pub fn nb_remaining(&self) -> usize {
+        self.pg.nb_remaining()
+    }
+}
+
+impl<'a, T> Iterator for PermutationsDirect<'a, T> {
+    type Item = PermutationRefs<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.nth_permutation(0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let nb_remaining = self.nb_remaining();
+        (nb_remaining, Some(nb_remaining))
+    }
+
+    fn count(self) -> usize {
+        self.nb_remaining()
+    }
+}
+
+impl<'a, T> Iterator for PermutationRefs<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.perm.next().map(|i| &self.elems[i as usize])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.perm.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_refs<T: PartialEq + std::fmt::Debug>(
+        ref_slice: &[T],
+        some_iter: Option<PermutationRefs<'_, T>>,
+    ) {
+        assert_eq!(
+            ref_slice.iter().collect::<Vec<_>>(),
+            some_iter.unwrap().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn permutation_nth() {
+        let list = ["foo", "bar", "baz"];
+        test_refs(&["foo", "bar", "baz"], list.permutation_nth(0).unwrap());
+        test_refs(&["baz", "bar", "foo"], list.permutation_nth(5).unwrap());
+        assert!(list.permutation_nth(6).unwrap().is_none());
+
+        let list = (0..12).collect::<Vec<u32>>();
+        test_refs(
+            &[1, 0, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+            list.permutation_nth(factorial128(11)).unwrap(),
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            [0; 33].permutation_nth(0).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn permutations_direct() {
+        let list = vec!['a', 'b', 'c'];
+        let perms = list
+            .permutations_direct()
+            .unwrap()
+            .map(|perm| perm.collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["abc", "acb", "bac", "bca", "cab", "cba"], perms);
+
+        let mut perms = list.permutations_direct().unwrap();
+        assert_eq!(6, perms.nb_remaining());
+        test_refs(&['b', 'c', 'a'], perms.nth_permutation(3));
+        assert_eq!(2, perms.nb_remaining());
+    }
+
+    #[test]
+    fn permutation_count() {
+        assert_eq!(Ok(24), [1, 2, 3, 4].permutation_count());
+        assert_eq!(Ok(factorial128(20)), vec![0; 20].permutation_count());
+        assert_eq!(Ok(0), Vec::<u8>::new().permutation_count());
+    }
+
+    #[test]
+    fn rank_of() {
+        let list = ["foo", "bar", "baz"];
+        assert_eq!(Ok(0), list.rank_of(&["foo", "bar", "baz"]));
+        assert_eq!(Ok(3), list.rank_of(&["bar", "baz", "foo"]));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            list.rank_of(&["bar", "bar", "foo"])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::LengthMismatch),
+            list.rank_of(&["bar", "foo"])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            Vec::<u8>::new().rank_of(&[])
+        );
+
+        let list = (0..20).collect::<Vec<u32>>();
+        let idx = 1234567890123;
+        let perm = list
+            .permutation_nth(idx)
+            .unwrap()
+            .unwrap()
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(Ok(idx), list.rank_of(&perm));
+    }
+}
@@ -1,6 +1,11 @@
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum PermutationGeneratorError {
     TooManyElements,
     SliceTooSmall,
+    NotAPermutation,
+    LengthMismatch,
+    OutOfRange,
+    NotInFamily,
 }
 pub type PResult<T> = Result<T, PermutationGeneratorError>;
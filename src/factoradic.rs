@@ -0,0 +1,253 @@
+use super::*;
+use std::fmt;
+
+/// The Lehmer code of a permutation of `0..nb_elems`, read as a number in the factorial base.
+///
+/// `digits[i]` counts the elements after position `i` smaller than `perm[i]`, so it is below `nb_elems - i`, and
+/// weighs `(nb_elems - 1 - i)!` in the rank. Factoradics of the same length compare as their ranks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Factoradic {
+    nb_elems: u8,
+    digits: [u8; 32],
+}
+
+impl Factoradic {
+    /// `OutOfRange` if a digit is out of range for its position.
+    pub fn new(digits: &[u8]) -> PResult<Self> {
+        if digits.len() > u128::MAX_ELEMENTS as usize {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        let nb_elems = digits.len() as u8;
+        let mut factoradic = Self {
+            nb_elems,
+            digits: [0; 32],
+        };
+        for (pos, &digit) in digits.iter().enumerate() {
+            if digit >= nb_elems - pos as u8 {
+                return Err(PermutationGeneratorError::OutOfRange);
+            }
+            factoradic.digits[pos] = digit;
+        }
+        Ok(factoradic)
+    }
+
+    /// The Lehmer code of `PermutationGenerator::nth_absolute(nb_elems, idx)`, `None` past the last permutation.
+    pub fn from_rank<R: Rank>(nb_elems: u8, mut idx: R) -> PResult<Option<Self>> {
+        if nb_elems > R::MAX_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        if idx >= R::factorial(nb_elems) {
+            return Ok(None);
+        }
+        let mut factoradic = Self {
+            nb_elems,
+            digits: [0; 32],
+        };
+        for (radix, digit) in (1..=nb_elems).zip(factoradic.digits_mut().iter_mut().rev()) {
+            *digit = (idx % R::from(radix)).as_u8();
+            idx /= R::from(radix);
+        }
+        Ok(Some(factoradic))
+    }
+
+    pub fn from_permutation(perm: &[u8]) -> PResult<Self> {
+        if perm.len() > u128::MAX_ELEMENTS as usize {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        let mut factoradic = Self {
+            nb_elems: perm.len() as u8,
+            digits: [0; 32],
+        };
+        let mut pos = 0;
+        walk_permutation(factoradic.nb_elems, perm, |elem, remaining| {
+            factoradic.digits[pos] = (remaining & ((1 << elem) - 1)).count_ones() as u8;
+            pos += 1;
+            Ok(())
+        })
+        .map(|_| factoradic)
+    }
+
+    pub fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    /// The Lehmer digits, most significant first.
+    pub fn digits(&self) -> &[u8] {
+        &self.digits[..self.nb_elems as usize]
+    }
+
+    /// `TooManyElements` if the rank does not fit `R`.
+    ///
+    /// Like `PermutationGenerator::rank`, `NotAPermutation` for the empty factoradic, which no rank decodes to.
+    pub fn rank<R: Rank>(&self) -> PResult<R> {
+        if self.nb_elems > R::MAX_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        if self.nb_elems == 0 {
+            return Err(PermutationGeneratorError::NotAPermutation);
+        }
+        Ok((1..=self.nb_elems)
+            .rev()
+            .zip(self.digits())
+            .fold(R::ZERO, |rank, (radix, &digit)| {
+                rank * R::from(radix) + R::from(digit)
+            }))
+    }
+
+    pub fn permutation(&self) -> PermutationBuffer {
+        let mut buffer = PermutationBuffer::new();
+        self.elems().for_each(|elem| buffer.push(elem));
+        buffer
+    }
+
+    /// The elements of the permutation, decoded one digit at a time.
+    pub fn elems(&self) -> impl Iterator<Item = u8> + '_ {
+        let mut remaining = (1u64 << self.nb_elems) - 1;
+        self.digits().iter().map(move |&digit| {
+            let mut smaller = remaining;
+            (0..digit).for_each(|_| smaller &= smaller - 1);
+            let elem = smaller.trailing_zeros() as u8;
+            remaining &= !(1 << elem);
+            elem
+        })
+    }
+
+    /// The digit-wise sum, carrying in the factorial base, `None` on overflow or if the lengths differ.
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        self.combine(other, |digit, other_digit, carry, radix| {
+            let sum = digit + other_digit + carry;
+            (sum % radix, sum / radix)
+        })
+    }
+
+    /// The digit-wise difference, borrowing in the factorial base, `None` on underflow or if the lengths differ.
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        self.combine(other, |digit, other_digit, borrow, radix| {
+            let subtrahend = other_digit + borrow;
+            if digit >= subtrahend {
+                (digit - subtrahend, 0)
+            } else {
+                (digit + radix - subtrahend, 1)
+            }
+        })
+    }
+
+    /// Combines the digits from the least significant one, `op(digit, other_digit, carry, radix)` returning the
+    /// new digit and the next carry.
+    fn combine(&self, other: &Self, op: impl Fn(u8, u8, u8, u8) -> (u8, u8)) -> Option<Self> {
+        if self.nb_elems != other.nb_elems {
+            return None;
+        }
+        let mut combined = *self;
+        let mut carry = 0;
+        for (radix, (digit, &other_digit)) in
+            (1..=self.nb_elems).zip(combined.digits_mut().iter_mut().zip(other.digits()).rev())
+        {
+            (*digit, carry) = op(*digit, other_digit, carry, radix);
+        }
+        (carry == 0).then_some(combined)
+    }
+
+    #[inline]
+    fn digits_mut(&mut self) -> &mut [u8] {
+        &mut self.digits[..self.nb_elems as usize]
+    }
+}
+
+/// The digits separated by colons, most significant first, e.g. `2:0:1:0`.
+impl fmt::Display for Factoradic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (pos, digit) in self.digits().iter().enumerate() {
+            if pos > 0 {
+                write!(f, ":")?;
+            }
+            write!(f, "{}", digit)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            &[2, 0, 1, 0],
+            Factoradic::new(&[2, 0, 1, 0]).unwrap().digits()
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::OutOfRange),
+            Factoradic::new(&[2, 0, 1, 1])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::TooManyElements),
+            Factoradic::new(&[0; 33])
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        let factoradic = Factoradic::from_rank(4, 13u16).unwrap().unwrap();
+        assert_eq!(&[2, 0, 1, 0], factoradic.digits());
+        assert_eq!(&[2, 0, 3, 1], factoradic.permutation().as_slice());
+        assert_eq!(Ok(factoradic), Factoradic::from_permutation(&[2, 0, 3, 1]));
+        assert_eq!(Ok(13u64), factoradic.rank());
+        assert_eq!(Ok(None), Factoradic::from_rank(4, 24u16));
+        assert_eq!(
+            Err(PermutationGeneratorError::TooManyElements),
+            Factoradic::new(&[0; 9]).unwrap().rank::<u16>()
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            Factoradic::from_permutation(&[0, 2])
+        );
+        assert_eq!(Ok(None), Factoradic::from_rank(0, 0u16));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            Factoradic::new(&[]).unwrap().rank::<u16>()
+        );
+    }
+
+    #[test]
+    fn matches_permutation_generator() {
+        for (idx, perm) in PermutationGenerator8::new(6).unwrap().enumerate() {
+            let perm = perm.collect::<Vec<_>>();
+            let factoradic = Factoradic::from_rank(6, idx as u16).unwrap().unwrap();
+            assert_eq!(perm.as_slice(), factoradic.permutation().as_slice());
+            assert_eq!(Ok(factoradic), Factoradic::from_permutation(&perm));
+            assert_eq!(Ok(idx as u16), factoradic.rank());
+        }
+        let idx = 123456789012345678901234567890123u128;
+        let factoradic = Factoradic::from_rank(32, idx).unwrap().unwrap();
+        assert_eq!(Ok(idx), factoradic.rank());
+        assert_eq!(
+            Ok(idx),
+            PermutationGenerator32::rank(factoradic.permutation().as_slice())
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = Factoradic::from_rank(10, 1234567u64).unwrap().unwrap();
+        let b = Factoradic::from_rank(10, 2000000u64).unwrap().unwrap();
+        assert_eq!(Ok(3234567u64), a.checked_add(&b).unwrap().rank());
+        assert_eq!(Ok(765433u64), b.checked_sub(&a).unwrap().rank());
+        assert_eq!(None, a.checked_sub(&b));
+        let last = Factoradic::from_rank(10, 3628799u64).unwrap().unwrap();
+        let one = Factoradic::from_rank(10, 1u64).unwrap().unwrap();
+        assert_eq!(None, last.checked_add(&one));
+        assert_eq!(None, a.checked_add(&Factoradic::new(&[0]).unwrap()));
+        assert!(a < b && b < last);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            "2:0:1:0",
+            Factoradic::new(&[2, 0, 1, 0]).unwrap().to_string()
+        );
+        assert_eq!("", Factoradic::new(&[]).unwrap().to_string());
+    }
+}
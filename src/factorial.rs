@@ -1,15 +1,32 @@
+use crate::numbers;
+
 macro_rules! impl_factorial {
-    ($fact:ident, $fact_type:ty) => {
+    ($fact:ident, $table:ident, $fact_type:ty, $len:expr, $numbers_fact:path) => {
+        /// `n!` for every `n` whose factorial fits the type, `0! == 1`, narrowed from `numbers`.
+        pub(crate) const $table: [$fact_type; $len] = {
+            let mut table = [1; $len];
+            let mut n = 1;
+            while n < $len {
+                table[n] = match $numbers_fact(n as u8) {
+                    Some(fact) => fact,
+                    None => panic!("The factorial overflows the type"),
+                };
+                n += 1;
+            }
+            table
+        };
+
+        /// The number of permutations of `nb_elems` elements, none for no elements.
         #[inline]
-        pub(crate) fn $fact(nb_elems: u8) -> $fact_type {
+        pub(crate) const fn $fact(nb_elems: u8) -> $fact_type {
             match nb_elems {
-                0 | 1 | 2 => nb_elems as $fact_type,
-                _ => (1..=nb_elems).map(|i| i as $fact_type).product(),
+                0 => 0,
+                _ => $table[nb_elems as usize],
             }
         }
     };
 }
 
-impl_factorial!(factorial16, u16);
-impl_factorial!(factorial64, u64);
-impl_factorial!(factorial128, u128);
+impl_factorial!(factorial16, FACTORIALS16, u16, 9, numbers::factorial16);
+impl_factorial!(factorial64, FACTORIALS64, u64, 21, numbers::factorial64);
+impl_factorial!(factorial128, FACTORIALS128, u128, 35, numbers::factorial128);
@@ -0,0 +1,238 @@
+use super::*;
+
+/// Alternating permutations, going up and down in turn, counted by the Euler zigzag numbers.
+///
+/// Up-down permutations start with an ascent, `p0 < p1 > p2 < ...`, down-up ones with a descent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AlternatingPermutations {
+    nb_elems: u8,
+    starts_up: bool,
+    entringer_numbers: [[u128; 33]; 33],
+}
+
+pub type AlternatingPermutationGenerator<R> = FamilyGenerator<AlternatingPermutations, R>;
+pub type AlternatingPermutationGenerator8 = AlternatingPermutationGenerator<u16>;
+pub type AlternatingPermutationGenerator16 = AlternatingPermutationGenerator<u64>;
+pub type AlternatingPermutationGenerator32 = AlternatingPermutationGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<AlternatingPermutations, R> {
+    /// Up-down permutations, `p0 < p1 > p2 < ...`.
+    pub fn new(nb_elems: u8) -> PResult<Self> {
+        AlternatingPermutations::new(nb_elems, true).and_then(Self::from_family)
+    }
+
+    /// Down-up permutations, `p0 > p1 < p2 > ...`.
+    pub fn down_up(nb_elems: u8) -> PResult<Self> {
+        AlternatingPermutations::new(nb_elems, false).and_then(Self::from_family)
+    }
+
+    pub fn nth_absolute(nb_elems: u8, idx: R) -> PResult<Option<PermutationBuffer>> {
+        Self::new(nb_elems).map(|ag| ag.permutation(idx))
+    }
+}
+
+impl AlternatingPermutations {
+    fn new(nb_elems: u8, starts_up: bool) -> PResult<Self> {
+        if nb_elems > u128::MAX_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        Ok(Self {
+            nb_elems,
+            starts_up,
+            entringer_numbers: entringer_numbers(nb_elems.saturating_sub(1)),
+        })
+    }
+
+    /// Whether the step to position `pos` goes up.
+    #[inline]
+    fn is_up(&self, pos: u8) -> bool {
+        (pos % 2 == 1) == self.starts_up
+    }
+
+    /// The number of alternating arrangements of `nb_remaining` values after the last placed one, `nb_below` of them
+    /// being smaller than it, with a first step up if `is_up`.
+    #[inline]
+    fn nb_completions<R: Rank>(&self, nb_remaining: u8, nb_below: u8, is_up: bool) -> R {
+        let nb_remaining = nb_remaining as usize;
+        let nb = if is_up {
+            self.entringer_numbers[nb_remaining][nb_below as usize]
+        } else {
+            self.entringer_numbers[nb_remaining][nb_remaining - nb_below as usize]
+        };
+        R::from_u128(nb).unwrap()
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for AlternatingPermutations {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        let count = (0..self.nb_elems).fold(0, |count, first| {
+            count + self.nb_completions::<u128>(self.nb_elems - 1, first, self.is_up(1))
+        });
+        R::from_u128(count).ok_or(PermutationGeneratorError::TooManyElements)
+    }
+
+    fn unrank(&self, mut idx: R, buffer: &mut PermutationBuffer) {
+        let mut remaining = (1u64 << self.nb_elems) - 1;
+        let mut previous = None;
+        for pos in 0..self.nb_elems {
+            let nb_after = self.nb_elems - 1 - pos;
+            for elem in (0..self.nb_elems).filter(|&elem| {
+                remaining & (1 << elem) != 0
+                    && previous.is_none_or(|previous| (elem > previous) == self.is_up(pos))
+            }) {
+                let nb_below = (remaining & ((1 << elem) - 1)).count_ones() as u8;
+                let nb = self.nb_completions::<R>(nb_after, nb_below, self.is_up(pos + 1));
+                if idx < nb {
+                    buffer.push(elem);
+                    remaining &= !(1 << elem);
+                    previous = Some(elem);
+                    break;
+                }
+                idx -= nb;
+            }
+        }
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        let mut rank = R::ZERO;
+        let mut pos = 0;
+        let mut previous: Option<u8> = None;
+        walk_permutation(self.nb_elems, perm, |elem, remaining| {
+            let is_allowed =
+                |elem: u8| previous.is_none_or(|previous| (elem > previous) == self.is_up(pos));
+            if !is_allowed(elem) {
+                return Err(PermutationGeneratorError::NotInFamily);
+            }
+            let nb_after = self.nb_elems - 1 - pos;
+            for smaller in
+                (0..elem).filter(|&smaller| remaining & (1 << smaller) != 0 && is_allowed(smaller))
+            {
+                let nb_below = (remaining & ((1 << smaller) - 1)).count_ones() as u8;
+                rank += self.nb_completions::<R>(nb_after, nb_below, self.is_up(pos + 1));
+            }
+            previous = Some(elem);
+            pos += 1;
+            Ok(())
+        })
+        .map(|_| rank)
+    }
+}
+
+/// The Entringer numbers `table[r][j]`, arranging `r` values with a first step up from a value above `j` of them.
+///
+/// The first value is one of the `r - j` larger ones, followed by a step down, which mirrors a step up.
+fn entringer_numbers(max_remaining: u8) -> [[u128; 33]; 33] {
+    let mut table = [[0; 33]; 33];
+    table[0][0] = 1;
+    for nb_remaining in 1..=max_remaining as usize {
+        let mut nb = 0;
+        for nb_below in (0..nb_remaining).rev() {
+            nb += table[nb_remaining - 1][nb_remaining - 1 - nb_below];
+            table[nb_remaining][nb_below] = nb;
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    fn is_alternating(perm: &[u8], starts_up: bool) -> bool {
+        perm.windows(2)
+            .enumerate()
+            .all(|(pos, pair)| (pair[0] < pair[1]) == ((pos % 2 == 0) == starts_up))
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            1385,
+            AlternatingPermutationGenerator8::new(8)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            1903757312,
+            AlternatingPermutationGenerator16::new(15)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            19391512145,
+            AlternatingPermutationGenerator16::down_up(16)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            1,
+            AlternatingPermutationGenerator8::new(1)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            AlternatingPermutationGenerator32::down_up(33).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn next_permutation() {
+        let mut ag = AlternatingPermutationGenerator8::new(4).unwrap();
+        test_slice(&[0, 2, 1, 3], ag.next_permutation());
+        test_slice(&[0, 3, 1, 2], ag.next_permutation());
+        test_slice(&[1, 2, 0, 3], ag.next_permutation());
+        test_slice(&[2, 3, 0, 1], ag.nth(1));
+        assert!(ag.next_permutation().is_none());
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        for starts_up in [true, false] {
+            let ag = if starts_up {
+                AlternatingPermutationGenerator8::new(8)
+            } else {
+                AlternatingPermutationGenerator8::down_up(8)
+            }
+            .unwrap();
+            let members = assert_matches_filtered(&ag, |perm| is_alternating(perm, starts_up));
+            assert!(members.is_sorted());
+        }
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[0, 2, 1, 4, 3, 6, 5, 8, 7, 10, 9, 12, 11, 14, 13, 15],
+            AlternatingPermutationGenerator16::nth_absolute(16, 0).unwrap(),
+        );
+        assert!(
+            AlternatingPermutationGenerator16::nth_absolute(16, 19391512145)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn rank() {
+        let ag = AlternatingPermutationGenerator32::down_up(32).unwrap();
+        let idx = 123456789012345678901234567;
+        let perm = ag.permutation(idx).unwrap();
+        assert!(is_alternating(&perm, false));
+        assert_eq!(Ok(idx), ag.rank(&perm));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            AlternatingPermutationGenerator8::new(4)
+                .unwrap()
+                .rank(&[0, 2, 3, 1])
+        );
+    }
+}
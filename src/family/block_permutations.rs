@@ -0,0 +1,257 @@
+use super::*;
+
+/// Permutations keeping each block of a partition of `0..nb_elems` contiguous, in any internal order.
+///
+/// Blocks are numbered by their smallest element, elements listed in no block form blocks of their own.
+/// The rank is `outer * inner_total + inner`, where `outer` ranks the order of the blocks and `inner`
+/// combines the internal orders with the first block as the most significant radix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BlockPermutations {
+    nb_elems: u8,
+    nb_blocks: u8,
+    masks: [u32; 32],
+    block_of: [u8; 32],
+}
+
+pub type BlockPermutationGenerator<R> = FamilyGenerator<BlockPermutations, R>;
+pub type BlockPermutationGenerator8 = BlockPermutationGenerator<u16>;
+pub type BlockPermutationGenerator16 = BlockPermutationGenerator<u64>;
+pub type BlockPermutationGenerator32 = BlockPermutationGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<BlockPermutations, R> {
+    /// `NotAPermutation` if the blocks overlap or name an element outside `0..nb_elems`.
+    pub fn new(nb_elems: u8, blocks: &[&[u8]]) -> PResult<Self> {
+        BlockPermutations::new(nb_elems, blocks).and_then(Self::from_family)
+    }
+
+    pub fn nth_absolute(
+        nb_elems: u8,
+        blocks: &[&[u8]],
+        idx: R,
+    ) -> PResult<Option<PermutationBuffer>> {
+        Self::new(nb_elems, blocks).map(|bg| bg.permutation(idx))
+    }
+}
+
+impl BlockPermutations {
+    fn new(nb_elems: u8, blocks: &[&[u8]]) -> PResult<Self> {
+        if nb_elems > 32 {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        let mut assigned = 0u32;
+        let mut masks = [0u32; 32];
+        let mut nb_blocks = 0;
+        for block in blocks.iter().filter(|block| !block.is_empty()) {
+            for &elem in block.iter() {
+                if elem >= nb_elems || assigned & (1 << elem) != 0 {
+                    return Err(PermutationGeneratorError::NotAPermutation);
+                }
+                assigned |= 1 << elem;
+                masks[nb_blocks] |= 1 << elem;
+            }
+            nb_blocks += 1;
+        }
+        for elem in (0..nb_elems).filter(|&elem| assigned & (1 << elem) == 0) {
+            masks[nb_blocks] = 1 << elem;
+            nb_blocks += 1;
+        }
+        masks[..nb_blocks].sort_unstable_by_key(|mask| mask.trailing_zeros());
+        let mut block_of = [0; 32];
+        for (block, &mask) in (0..).zip(&masks[..nb_blocks]) {
+            for elem in (0..nb_elems).filter(|&elem| mask & (1 << elem) != 0) {
+                block_of[elem as usize] = block;
+            }
+        }
+        Ok(Self {
+            nb_elems,
+            nb_blocks: nb_blocks as u8,
+            masks,
+            block_of,
+        })
+    }
+
+    /// The number of internal orders of all the blocks together.
+    #[inline]
+    fn nb_inner<R: Rank>(&self) -> R {
+        self.masks[..self.nb_blocks as usize]
+            .iter()
+            .fold(R::ONE, |nb_inner, mask| {
+                nb_inner * R::factorial(mask.count_ones() as u8)
+            })
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for BlockPermutations {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        self.masks[..self.nb_blocks as usize]
+            .iter()
+            .try_fold(R::factorial(self.nb_blocks), |count, mask| {
+                count.checked_mul(R::factorial(mask.count_ones() as u8))
+            })
+            .ok_or(PermutationGeneratorError::TooManyElements)
+    }
+
+    fn unrank(&self, idx: R, buffer: &mut PermutationBuffer) {
+        let nb_inner = self.nb_inner::<R>();
+        let mut inner_idx = idx % nb_inner;
+        let mut inner_perms = [[0; 32]; 32];
+        for block in (0..self.nb_blocks as usize).rev() {
+            let mask = self.masks[block];
+            let nb_block_elems = mask.count_ones() as u8;
+            let nb_block_perms = R::factorial(nb_block_elems);
+            let block_elems = (0..self.nb_elems)
+                .filter(|&elem| mask & (1 << elem) != 0)
+                .collect::<PermutationBuffer>();
+            R::permutation(nb_block_elems, nb_block_perms, inner_idx % nb_block_perms)
+                .into_iter()
+                .flatten()
+                .zip(inner_perms[block].iter_mut())
+                .for_each(|(elem_nb, elem)| *elem = block_elems[elem_nb as usize]);
+            inner_idx /= nb_block_perms;
+        }
+        R::permutation(self.nb_blocks, R::factorial(self.nb_blocks), idx / nb_inner)
+            .into_iter()
+            .flatten()
+            .for_each(|block| {
+                let nb_block_elems = self.masks[block as usize].count_ones() as usize;
+                inner_perms[block as usize][..nb_block_elems]
+                    .iter()
+                    .for_each(|&elem| buffer.push(elem));
+            });
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        check_permutation(perm)?;
+        let mut inner_ranks = [R::ZERO; 32];
+        let mut outer = PermutationBuffer::new();
+        let mut pos = 0;
+        while pos < perm.len() {
+            let block = self.block_of[perm[pos] as usize];
+            let mask = self.masks[block as usize];
+            let nb_block_elems = mask.count_ones() as u8;
+            let segment = &perm[pos..(pos + nb_block_elems as usize).min(perm.len())];
+            if segment
+                .iter()
+                .any(|&elem| self.block_of[elem as usize] != block)
+            {
+                return Err(PermutationGeneratorError::NotInFamily);
+            }
+            inner_ranks[block as usize] = lehmer_rank(
+                nb_block_elems,
+                segment
+                    .iter()
+                    .map(|&elem| (mask & ((1 << elem) - 1)).count_ones() as u8),
+            )
+            .map_err(|_| PermutationGeneratorError::NotInFamily)?;
+            outer.push(block);
+            pos += nb_block_elems as usize;
+        }
+        let inner = self.masks[..self.nb_blocks as usize]
+            .iter()
+            .zip(inner_ranks)
+            .fold(R::ZERO, |inner, (mask, inner_rank)| {
+                inner * R::factorial(mask.count_ones() as u8) + inner_rank
+            });
+        lehmer_rank::<R>(self.nb_blocks, outer.iter().copied())
+            .map(|outer_rank| outer_rank * self.nb_inner::<R>() + inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            24 * 2 * 6,
+            BlockPermutationGenerator8::new(7, &[&[1, 4], &[6, 0, 3]])
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(1, BlockPermutationGenerator8::new(1, &[]).unwrap().count());
+        assert_eq!(
+            PermutationGeneratorError::NotAPermutation,
+            BlockPermutationGenerator8::new(4, &[&[0, 1], &[1, 2]]).unwrap_err()
+        );
+        assert_eq!(
+            PermutationGeneratorError::NotAPermutation,
+            BlockPermutationGenerator8::new(4, &[&[0, 4]]).unwrap_err()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            BlockPermutationGenerator8::new(9, &[]).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn iter() {
+        let perms = BlockPermutationGenerator8::new(3, &[&[0, 2]])
+            .unwrap()
+            .map(|perm| perm.collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![vec![0, 2, 1], vec![2, 0, 1], vec![1, 0, 2], vec![1, 2, 0]],
+            perms
+        );
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        let bg = BlockPermutationGenerator8::new(7, &[&[1, 4], &[6, 0, 3]]).unwrap();
+        let members = assert_matches_filtered(&bg, |perm| {
+            let pos = |elem| perm.iter().position(|&e| e == elem).unwrap();
+            pos(1).abs_diff(pos(4)) == 1
+                && [pos(0), pos(3), pos(6)].iter().max().unwrap()
+                    - [pos(0), pos(3), pos(6)].iter().min().unwrap()
+                    == 2
+        });
+        assert_eq!(288, members.len());
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[3, 2, 1, 0, 4, 5, 6, 7, 8, 9],
+            BlockPermutationGenerator16::nth_absolute(10, &[&[0, 1, 2, 3]], 23).unwrap(),
+        );
+        test_slice(
+            &[9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+            BlockPermutationGenerator16::nth_absolute(10, &[&[0, 1, 2, 3]], 24 * 5040 - 1).unwrap(),
+        );
+        assert!(
+            BlockPermutationGenerator16::nth_absolute(10, &[&[0, 1, 2, 3]], 24 * 5040)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn rank() {
+        let bg = BlockPermutationGenerator32::new(30, &[&[29, 0, 15], &[3, 4, 5, 6, 7]]).unwrap();
+        let idx = 123456789012345678901234567;
+        let perm = bg.permutation(idx).unwrap();
+        assert_eq!(Ok(idx), bg.rank(&perm));
+        let bg = BlockPermutationGenerator8::new(4, &[&[0, 1]]).unwrap();
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            bg.rank(&[0, 2, 1, 3])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            bg.rank(&[2, 0, 3, 1])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            bg.rank(&[0, 1, 1, 3])
+        );
+    }
+}
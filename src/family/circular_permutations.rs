@@ -0,0 +1,261 @@
+use super::*;
+
+/// Arrangements around a circle, one representative per rotation class, and optionally per reflection class too.
+///
+/// Representatives start with `0`. Up to reflection, the element after `0` is also smaller than the last element.
+/// There are `(n-1)!` classes up to rotation, and `(n-1)!/2` up to rotation and reflection for `n > 2`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CircularPermutations {
+    nb_elems: u8,
+    up_to_reflection: bool,
+}
+
+pub type CircularPermutationGenerator<R> = FamilyGenerator<CircularPermutations, R>;
+pub type CircularPermutationGenerator8 = CircularPermutationGenerator<u16>;
+pub type CircularPermutationGenerator16 = CircularPermutationGenerator<u64>;
+pub type CircularPermutationGenerator32 = CircularPermutationGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<CircularPermutations, R> {
+    /// One representative per rotation class.
+    pub fn new(nb_elems: u8) -> PResult<Self> {
+        Self::from_family(CircularPermutations {
+            nb_elems,
+            up_to_reflection: false,
+        })
+    }
+
+    /// One representative per class of rotations and reflections.
+    pub fn up_to_reflection(nb_elems: u8) -> PResult<Self> {
+        Self::from_family(CircularPermutations {
+            nb_elems,
+            up_to_reflection: true,
+        })
+    }
+
+    pub fn nth_absolute(nb_elems: u8, idx: R) -> PResult<Option<PermutationBuffer>> {
+        Self::new(nb_elems).map(|cg| cg.permutation(idx))
+    }
+
+    /// The representative of the class of `arrangement`, and its rank.
+    pub fn canonicalize(&self, arrangement: &[u8]) -> PResult<(PermutationBuffer, R)> {
+        let nb_elems = self.family.nb_elems as usize;
+        if arrangement.len() != nb_elems {
+            return Err(PermutationGeneratorError::LengthMismatch);
+        }
+        check_permutation(arrangement)?;
+        let start = arrangement.iter().position(|&elem| elem == 0).unwrap_or(0);
+        let mut canonical = arrangement[start..]
+            .iter()
+            .chain(&arrangement[..start])
+            .copied()
+            .collect::<PermutationBuffer>();
+        if self.family.up_to_reflection && nb_elems > 2 && canonical[1] > canonical[nb_elems - 1] {
+            canonical[1..].reverse();
+        }
+        self.rank(&canonical).map(|rank| (canonical, rank))
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for CircularPermutations {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        Ok(match (self.up_to_reflection, self.nb_elems) {
+            (true, 3..) => R::factorial(self.nb_elems - 1) / R::from(2),
+            _ => nb_arrangements(self.nb_elems.saturating_sub(1)),
+        })
+    }
+
+    fn unrank(&self, idx: R, buffer: &mut PermutationBuffer) {
+        buffer.push(0);
+        let nb_tail = self.nb_elems - 1;
+        if !self.up_to_reflection || nb_tail < 2 {
+            R::permutation(nb_tail, R::factorial(nb_tail), idx)
+                .into_iter()
+                .flatten()
+                .for_each(|elem| buffer.push(elem + 1));
+        } else {
+            unrank_first_below_last(nb_tail, idx, buffer);
+        }
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        check_permutation(perm)?;
+        let Some((&first, tail)) = perm.split_first() else {
+            return Ok(R::ZERO);
+        };
+        if first != 0 {
+            return Err(PermutationGeneratorError::NotInFamily);
+        }
+        if !self.up_to_reflection || tail.len() < 2 {
+            lehmer_rank(self.nb_elems - 1, tail.iter().map(|elem| elem - 1))
+        } else if tail[0] > tail[tail.len() - 1] {
+            Err(PermutationGeneratorError::NotInFamily)
+        } else {
+            Ok(rank_first_below_last(tail))
+        }
+    }
+}
+
+/// Pushes the `idx`-th permutation of `1..=nb_tail` whose first element is smaller than its last.
+fn unrank_first_below_last<R: Rank>(nb_tail: u8, mut idx: R, buffer: &mut PermutationBuffer) {
+    let mut remaining = (1u64 << nb_tail) - 1;
+    let mut first = 0;
+    for pos in 0..nb_tail {
+        for elem in (0..nb_tail).filter(|&elem| remaining & (1 << elem) != 0) {
+            let nb = nb_completions::<R>(pos, first, elem, remaining & !(1 << elem));
+            if idx < nb {
+                if pos == 0 {
+                    first = elem;
+                }
+                buffer.push(elem + 1);
+                remaining &= !(1 << elem);
+                break;
+            }
+            idx -= nb;
+        }
+    }
+}
+
+fn rank_first_below_last<R: Rank>(tail: &[u8]) -> R {
+    let mut remaining = (1u64 << tail.len()) - 1;
+    let first = tail[0] - 1;
+    let mut rank = R::ZERO;
+    for (pos, elem) in (0..).zip(tail.iter().map(|elem| elem - 1)) {
+        for smaller in (0..elem).filter(|&smaller| remaining & (1 << smaller) != 0) {
+            rank += nb_completions::<R>(pos, first, smaller, remaining & !(1 << smaller));
+        }
+        remaining &= !(1 << elem);
+    }
+    rank
+}
+
+/// The number of ways to complete a tail after placing `elem` at `pos`, leaving `rest`.
+#[inline]
+fn nb_completions<R: Rank>(pos: u8, first: u8, elem: u8, rest: u64) -> R {
+    let first = if pos == 0 { elem } else { first };
+    match rest.count_ones() as u8 {
+        0 if elem > first => R::ONE,
+        0 => R::ZERO,
+        nb_rest => R::from((rest >> (first + 1)).count_ones() as u8) * nb_arrangements(nb_rest - 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            5040,
+            CircularPermutationGenerator8::new(8)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            2520,
+            CircularPermutationGenerator8::up_to_reflection(8)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            1,
+            CircularPermutationGenerator8::up_to_reflection(2)
+                .unwrap()
+                .count()
+        );
+        assert_eq!(1, CircularPermutationGenerator8::new(1).unwrap().count());
+    }
+
+    #[test]
+    fn iter() {
+        let perms = CircularPermutationGenerator8::new(4)
+            .unwrap()
+            .map(|perm| perm.collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                vec![0, 1, 2, 3],
+                vec![0, 1, 3, 2],
+                vec![0, 2, 1, 3],
+                vec![0, 2, 3, 1],
+                vec![0, 3, 1, 2],
+                vec![0, 3, 2, 1],
+            ],
+            perms
+        );
+
+        let perms = CircularPermutationGenerator8::up_to_reflection(4)
+            .unwrap()
+            .map(|perm| perm.collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![vec![0, 1, 2, 3], vec![0, 1, 3, 2], vec![0, 2, 1, 3]],
+            perms
+        );
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        let cg = CircularPermutationGenerator8::up_to_reflection(7).unwrap();
+        let members = assert_matches_filtered(&cg, |perm| perm[0] == 0 && perm[1] < perm[6]);
+        assert_eq!(360, members.len());
+        assert!(members.is_sorted());
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[0, 2, 1, 3, 4, 5, 6, 7, 8, 9],
+            CircularPermutationGenerator16::nth_absolute(10, factorial64(8)).unwrap(),
+        );
+        let cg = CircularPermutationGenerator32::up_to_reflection(30).unwrap();
+        let last = cg.permutation(cg.nb_permutations() - 1).unwrap();
+        assert_eq!(0, last[0]);
+        assert_eq!(28, last[1]);
+        assert_eq!(29, last[29]);
+        assert_eq!(Ok(cg.nb_permutations() - 1), cg.rank(&last));
+    }
+
+    #[test]
+    fn canonicalize() {
+        let cg = CircularPermutationGenerator8::new(5).unwrap();
+        let (canonical, rank) = cg.canonicalize(&[3, 4, 0, 2, 1]).unwrap();
+        assert_eq!(&[0, 2, 1, 3, 4], canonical.as_slice());
+        assert_eq!(Ok(rank), cg.rank(&canonical));
+
+        let cg = CircularPermutationGenerator8::up_to_reflection(5).unwrap();
+        let (canonical, rank) = cg.canonicalize(&[3, 4, 0, 2, 1]).unwrap();
+        assert_eq!(&[0, 2, 1, 3, 4], canonical.as_slice());
+        let (reflected, reflected_rank) = cg.canonicalize(&[1, 2, 0, 4, 3]).unwrap();
+        assert_eq!(canonical, reflected);
+        assert_eq!(rank, reflected_rank);
+
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            cg.canonicalize(&[3, 4, 0, 2, 2])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::LengthMismatch),
+            cg.canonicalize(&[3, 4, 0, 2])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            cg.rank(&[0, 4, 1, 3, 2])
+        );
+
+        let cg = CircularPermutationGenerator8::new(0).unwrap();
+        assert_eq!(Err(PermutationGeneratorError::NotInFamily), cg.rank(&[]));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            cg.canonicalize(&[])
+        );
+    }
+}
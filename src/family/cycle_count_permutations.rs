@@ -0,0 +1,249 @@
+use super::*;
+
+/// Permutations with exactly `nb_cycles` cycles, counted by the unsigned Stirling numbers of the first kind.
+///
+/// A prefix of a permutation closes some cycles and leaves one open path per position still to fill.
+/// Any arrangement of these paths completes it, so the completions with `j` more cycles are counted by `c(r, j)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CycleCountPermutations {
+    nb_elems: u8,
+    nb_cycles: u8,
+    stirling_first_kind: [[u128; 33]; 33],
+}
+
+pub type CycleCountGenerator<R> = FamilyGenerator<CycleCountPermutations, R>;
+pub type CycleCountGenerator8 = CycleCountGenerator<u16>;
+pub type CycleCountGenerator16 = CycleCountGenerator<u64>;
+pub type CycleCountGenerator32 = CycleCountGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<CycleCountPermutations, R> {
+    pub fn new(nb_elems: u8, nb_cycles: u8) -> PResult<Self> {
+        CycleCountPermutations::new(nb_elems, nb_cycles).and_then(Self::from_family)
+    }
+
+    pub fn nth_absolute(nb_elems: u8, nb_cycles: u8, idx: R) -> PResult<Option<PermutationBuffer>> {
+        Self::new(nb_elems, nb_cycles).map(|cg| cg.permutation(idx))
+    }
+}
+
+/// The open paths of a prefix, each going from an element not yet an image to a position not yet filled.
+struct OpenPaths {
+    head: [u8; 32],
+    tail: [u8; 32],
+    nb_closed: u8,
+}
+
+impl OpenPaths {
+    fn new() -> Self {
+        let mut ids = [0; 32];
+        (0..32).for_each(|elem| ids[elem as usize] = elem);
+        Self {
+            head: ids,
+            tail: ids,
+            nb_closed: 0,
+        }
+    }
+
+    /// Whether `pos` mapped to `elem` closes the cycle of `pos`.
+    #[inline]
+    fn closes(&self, pos: u8, elem: u8) -> bool {
+        self.head[pos as usize] == elem
+    }
+
+    fn map(&mut self, pos: u8, elem: u8) {
+        if self.closes(pos, elem) {
+            self.nb_closed += 1;
+        } else {
+            let head = self.head[pos as usize];
+            let tail = self.tail[elem as usize];
+            self.head[tail as usize] = head;
+            self.tail[head as usize] = tail;
+        }
+    }
+}
+
+impl CycleCountPermutations {
+    fn new(nb_elems: u8, nb_cycles: u8) -> PResult<Self> {
+        if nb_elems > u128::MAX_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        Ok(Self {
+            nb_elems,
+            nb_cycles,
+            stirling_first_kind: stirling_first_kind(nb_elems),
+        })
+    }
+
+    /// The number of completions after mapping `pos` to `elem`, with `nb_remaining` positions left including `pos`.
+    #[inline]
+    fn nb_completions<R: Rank>(&self, paths: &OpenPaths, pos: u8, elem: u8, nb_remaining: u8) -> R {
+        let nb_closed = paths.nb_closed + paths.closes(pos, elem) as u8;
+        match self.nb_cycles.checked_sub(nb_closed) {
+            Some(nb_cycles) if nb_cycles < nb_remaining => R::from_u128(
+                self.stirling_first_kind[nb_remaining as usize - 1][nb_cycles as usize],
+            )
+            .unwrap(),
+            _ => R::ZERO,
+        }
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for CycleCountPermutations {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        if self.nb_cycles > self.nb_elems {
+            return Ok(R::ZERO);
+        }
+        R::from_u128(self.stirling_first_kind[self.nb_elems as usize][self.nb_cycles as usize])
+            .ok_or(PermutationGeneratorError::TooManyElements)
+    }
+
+    fn unrank(&self, mut idx: R, buffer: &mut PermutationBuffer) {
+        let mut paths = OpenPaths::new();
+        let mut remaining = (1u64 << self.nb_elems) - 1;
+        for pos in 0..self.nb_elems {
+            let nb_remaining = self.nb_elems - pos;
+            for elem in (0..self.nb_elems).filter(|&elem| remaining & (1 << elem) != 0) {
+                let nb = self.nb_completions(&paths, pos, elem, nb_remaining);
+                if idx < nb {
+                    buffer.push(elem);
+                    paths.map(pos, elem);
+                    remaining &= !(1 << elem);
+                    break;
+                }
+                idx -= nb;
+            }
+        }
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        let mut paths = OpenPaths::new();
+        let mut rank = R::ZERO;
+        let mut pos = 0;
+        walk_permutation(self.nb_elems, perm, |elem, remaining| {
+            let nb_remaining = self.nb_elems - pos;
+            for smaller in (0..elem).filter(|&smaller| remaining & (1 << smaller) != 0) {
+                rank += self.nb_completions(&paths, pos, smaller, nb_remaining);
+            }
+            if self.nb_completions::<R>(&paths, pos, elem, nb_remaining) == R::ZERO {
+                return Err(PermutationGeneratorError::NotInFamily);
+            }
+            paths.map(pos, elem);
+            pos += 1;
+            Ok(())
+        })
+        .map(|_| rank)
+    }
+}
+
+/// The unsigned Stirling numbers of the first kind `c(0..=nb_elems, 0..=nb_elems)`.
+fn stirling_first_kind(nb_elems: u8) -> [[u128; 33]; 33] {
+    let mut stirling = [[0; 33]; 33];
+    for n in 0..=nb_elems {
+        for k in 0..=n {
+            stirling[n as usize][k as usize] = numbers::stirling_first128(n, k).unwrap();
+        }
+    }
+    stirling
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    fn nb_cycles(perm: &[u8]) -> u8 {
+        let mut seen = 0u64;
+        let mut nb_cycles = 0;
+        for start in 0..perm.len() {
+            if seen & (1 << start) == 0 {
+                nb_cycles += 1;
+                let mut elem = start;
+                while seen & (1 << elem) == 0 {
+                    seen |= 1 << elem;
+                    elem = perm[elem] as usize;
+                }
+            }
+        }
+        nb_cycles
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            13132,
+            CycleCountGenerator8::new(8, 3).unwrap().nb_permutations()
+        );
+        assert_eq!(
+            1,
+            CycleCountGenerator16::new(16, 16)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            1307674368000,
+            CycleCountGenerator16::new(16, 1).unwrap().nb_permutations()
+        );
+        assert_eq!(
+            0,
+            CycleCountGenerator16::new(16, 17)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            CycleCountGenerator32::new(33, 1).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn next_permutation() {
+        let mut cg = CycleCountGenerator8::new(3, 1).unwrap();
+        test_slice(&[1, 2, 0], cg.next_permutation());
+        test_slice(&[2, 0, 1], cg.next_permutation());
+        assert!(cg.next_permutation().is_none());
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        for cycles in 0..=8 {
+            let cg = CycleCountGenerator8::new(7, cycles).unwrap();
+            let members = assert_matches_filtered(&cg, |perm| nb_cycles(perm) == cycles);
+            assert!(members.is_sorted());
+        }
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[1, 2, 3, 4, 5, 6, 7, 8, 9, 0],
+            CycleCountGenerator16::nth_absolute(10, 1, 0).unwrap(),
+        );
+        test_slice(
+            &[9, 8, 7, 6, 5, 3, 2, 1, 0, 4],
+            CycleCountGenerator16::nth_absolute(10, 1, 362879).unwrap(),
+        );
+        assert!(CycleCountGenerator16::nth_absolute(10, 1, 362880)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rank() {
+        let cg = CycleCountGenerator32::new(32, 5).unwrap();
+        let idx = 1234567890123456789012345678901234;
+        let perm = cg.permutation(idx).unwrap();
+        assert_eq!(5, nb_cycles(&perm));
+        assert_eq!(Ok(idx), cg.rank(&perm));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            CycleCountGenerator8::new(3, 1).unwrap().rank(&[0, 2, 1])
+        );
+    }
+}
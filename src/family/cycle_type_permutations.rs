@@ -0,0 +1,302 @@
+use super::*;
+
+/// Permutations with a given cycle type, i.e. a conjugacy class, listed by the lengths of their cycles in any order.
+///
+/// The members are ordered by their canonical cycle notation rather than lexicographically: the cycle of the
+/// smallest element comes first, by increasing length then by the arrangement of its other elements, followed by
+/// the cycles of the elements left, in the same order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CycleTypePermutations {
+    nb_elems: u8,
+    multiplicities: [u8; 33],
+}
+
+pub type CycleTypeGenerator<R> = FamilyGenerator<CycleTypePermutations, R>;
+pub type CycleTypeGenerator8 = CycleTypeGenerator<u16>;
+pub type CycleTypeGenerator16 = CycleTypeGenerator<u64>;
+pub type CycleTypeGenerator32 = CycleTypeGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<CycleTypePermutations, R> {
+    /// Permutations of `cycle_lengths.iter().sum()` elements, `NotAPermutation` if a length is 0.
+    pub fn new(cycle_lengths: &[u8]) -> PResult<Self> {
+        CycleTypePermutations::new(cycle_lengths).and_then(Self::from_family)
+    }
+
+    pub fn nth_absolute(cycle_lengths: &[u8], idx: R) -> PResult<Option<PermutationBuffer>> {
+        Self::new(cycle_lengths).map(|cg| cg.permutation(idx))
+    }
+}
+
+impl CycleTypePermutations {
+    fn new(cycle_lengths: &[u8]) -> PResult<Self> {
+        if cycle_lengths.contains(&0) {
+            return Err(PermutationGeneratorError::NotAPermutation);
+        }
+        let nb_elems = cycle_lengths.iter().map(|&len| len as usize).sum::<usize>();
+        if nb_elems > u128::MAX_ELEMENTS as usize {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        let mut multiplicities = [0; 33];
+        cycle_lengths
+            .iter()
+            .for_each(|&len| multiplicities[len as usize] += 1);
+        Ok(Self {
+            nb_elems: nb_elems as u8,
+            multiplicities,
+        })
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for CycleTypePermutations {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        Ok(nb_class_members(&self.multiplicities, self.nb_elems))
+    }
+
+    fn unrank(&self, mut idx: R, buffer: &mut PermutationBuffer) {
+        let mut multiplicities = self.multiplicities;
+        let mut images = [0; 32];
+        let mut remaining = (1u64 << self.nb_elems) - 1;
+        while remaining != 0 {
+            let nb_remaining = remaining.count_ones() as u8;
+            let first = remaining.trailing_zeros() as u8;
+            remaining &= !(1 << first);
+            let mut arrangement_idx = R::ZERO;
+            let mut len = 0;
+            for candidate in 1..=nb_remaining {
+                if multiplicities[candidate as usize] == 0 {
+                    continue;
+                }
+                multiplicities[candidate as usize] -= 1;
+                let nb_rest = nb_class_members::<R>(&multiplicities, nb_remaining - candidate);
+                let nb = nb_falling::<R>(nb_remaining - 1, candidate - 1) * nb_rest;
+                if idx < nb {
+                    arrangement_idx = idx / nb_rest;
+                    idx = idx % nb_rest;
+                    len = candidate;
+                    break;
+                }
+                multiplicities[candidate as usize] += 1;
+                idx -= nb;
+            }
+            let mut previous = first;
+            for nb_placed in 1..len {
+                let place = nb_falling::<R>(nb_remaining - 1 - nb_placed, len - 1 - nb_placed);
+                let digit = (arrangement_idx / place).as_u8();
+                arrangement_idx = arrangement_idx % place;
+                let elem = nth_bit(remaining, digit);
+                remaining &= !(1 << elem);
+                images[previous as usize] = elem;
+                previous = elem;
+            }
+            images[previous as usize] = first;
+        }
+        images[..self.nb_elems as usize]
+            .iter()
+            .for_each(|&elem| buffer.push(elem));
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        check_permutation(perm)?;
+        let mut multiplicities = self.multiplicities;
+        let mut rank = R::ZERO;
+        let mut remaining = (1u64 << self.nb_elems) - 1;
+        while remaining != 0 {
+            let nb_remaining = remaining.count_ones() as u8;
+            let first = remaining.trailing_zeros() as u8;
+            remaining &= !(1 << first);
+            let mut cycle = [0; 32];
+            let mut len = 0;
+            let mut elem = perm[first as usize];
+            while elem != first {
+                cycle[len] = elem;
+                len += 1;
+                elem = perm[elem as usize];
+            }
+            let len = len as u8 + 1;
+            if multiplicities[len as usize] == 0 {
+                return Err(PermutationGeneratorError::NotInFamily);
+            }
+            for candidate in 1..len {
+                if multiplicities[candidate as usize] == 0 {
+                    continue;
+                }
+                multiplicities[candidate as usize] -= 1;
+                rank += nb_falling::<R>(nb_remaining - 1, candidate - 1)
+                    * nb_class_members(&multiplicities, nb_remaining - candidate);
+                multiplicities[candidate as usize] += 1;
+            }
+            multiplicities[len as usize] -= 1;
+            let mut arrangement_rank = R::ZERO;
+            for (nb_placed, &elem) in (1..len).zip(&cycle) {
+                let digit = (remaining & ((1 << elem) - 1)).count_ones() as u8;
+                arrangement_rank +=
+                    R::from(digit) * nb_falling(nb_remaining - 1 - nb_placed, len - 1 - nb_placed);
+                remaining &= !(1 << elem);
+            }
+            rank += arrangement_rank * nb_class_members(&multiplicities, nb_remaining - len);
+        }
+        Ok(rank)
+    }
+}
+
+/// The number of permutations of `nb_elems` elements with `multiplicities[len]` cycles of each length,
+/// `n! / prod(len^m * m!)`.
+fn nb_class_members<R: Rank>(multiplicities: &[u8; 33], nb_elems: u8) -> R {
+    let total_len = (1..33u8)
+        .map(|len| len as usize * multiplicities[len as usize] as usize)
+        .sum::<usize>();
+    if total_len != nb_elems as usize {
+        return R::ZERO;
+    }
+    let mut centralizer = R::ONE;
+    for (len, &multiplicity) in multiplicities.iter().enumerate().skip(1) {
+        for nb in 1..=multiplicity {
+            centralizer *= R::from(len as u8) * R::from(nb);
+        }
+    }
+    nb_arrangements::<R>(nb_elems) / centralizer
+}
+
+/// The number of arrangements of `nb_picked` among `nb_elems`, `nb_elems! / (nb_elems - nb_picked)!`.
+#[inline]
+fn nb_falling<R: Rank>(nb_elems: u8, nb_picked: u8) -> R {
+    R::from_u128(numbers::falling_factorial128(nb_elems, nb_picked).unwrap()).unwrap()
+}
+
+/// The `nth` set bit of `mask`.
+#[inline]
+fn nth_bit(mut mask: u64, nth: u8) -> u8 {
+    (0..nth).for_each(|_| mask &= mask - 1);
+    mask.trailing_zeros() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    fn cycle_type(perm: &[u8]) -> Vec<u8> {
+        let mut seen = 0u64;
+        let mut lengths = vec![];
+        for start in 0..perm.len() {
+            let mut len = 0;
+            let mut elem = start;
+            while seen & (1 << elem) == 0 {
+                seen |= 1 << elem;
+                elem = perm[elem] as usize;
+                len += 1;
+            }
+            if len > 0 {
+                lengths.push(len);
+            }
+        }
+        lengths.sort_unstable();
+        lengths
+    }
+
+    fn partitions(nb_elems: u8, max_part: u8) -> Vec<Vec<u8>> {
+        if nb_elems == 0 {
+            return vec![vec![]];
+        }
+        (1..=nb_elems.min(max_part))
+            .flat_map(|part| {
+                partitions(nb_elems - part, part)
+                    .into_iter()
+                    .map(move |mut rest| {
+                        rest.push(part);
+                        rest
+                    })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            210,
+            CycleTypeGenerator8::new(&[3, 2, 2])
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            5040,
+            CycleTypeGenerator8::new(&[8]).unwrap().nb_permutations()
+        );
+        assert_eq!(
+            2027025,
+            CycleTypeGenerator16::new(&[2; 8])
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            PermutationGeneratorError::NotAPermutation,
+            CycleTypeGenerator8::new(&[2, 0]).unwrap_err()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            CycleTypeGenerator8::new(&[5, 4]).unwrap_err()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            CycleTypeGenerator32::new(&[30, 3]).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn next_permutation() {
+        let mut cg = CycleTypeGenerator8::new(&[2, 2]).unwrap();
+        test_slice(&[1, 0, 3, 2], cg.next_permutation());
+        test_slice(&[2, 3, 0, 1], cg.next_permutation());
+        test_slice(&[3, 2, 1, 0], cg.next_permutation());
+        assert!(cg.next_permutation().is_none());
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        let mut total = 0;
+        for lengths in partitions(7, 7) {
+            let cg = CycleTypeGenerator8::new(&lengths).unwrap();
+            total += assert_matches_filtered(&cg, |perm| cycle_type(perm) == lengths).len();
+        }
+        assert_eq!(5040, total);
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[0, 1, 2, 4, 3],
+            CycleTypeGenerator8::nth_absolute(&[1, 2, 1, 1], 0).unwrap(),
+        );
+        test_slice(
+            &[1, 0, 3, 4, 2],
+            CycleTypeGenerator8::nth_absolute(&[2, 3], 0).unwrap(),
+        );
+        test_slice(
+            &[4, 2, 1, 0, 3],
+            CycleTypeGenerator8::nth_absolute(&[2, 3], 19).unwrap(),
+        );
+        assert!(CycleTypeGenerator8::nth_absolute(&[2, 3], 20)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rank() {
+        let cg = CycleTypeGenerator32::new(&[1, 1, 2, 3, 5, 8, 12]).unwrap();
+        let idx = cg.nb_permutations() / 7;
+        let perm = cg.permutation(idx).unwrap();
+        assert_eq!(vec![1, 1, 2, 3, 5, 8, 12], cycle_type(&perm));
+        assert_eq!(Ok(idx), cg.rank(&perm));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            CycleTypeGenerator8::new(&[2, 1]).unwrap().rank(&[1, 2, 0])
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use super::*;
+
+/// Permutations without fixed points, counted by the subfactorial `!n`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Derangements {
+    nb_elems: u8,
+}
+
+pub type DerangementGenerator<R> = FamilyGenerator<Derangements, R>;
+pub type DerangementGenerator8 = DerangementGenerator<u16>;
+pub type DerangementGenerator16 = DerangementGenerator<u64>;
+pub type DerangementGenerator32 = DerangementGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<Derangements, R> {
+    pub fn new(nb_elems: u8) -> PResult<Self> {
+        Self::from_family(Derangements { nb_elems })
+    }
+
+    pub fn nth_absolute(nb_elems: u8, idx: R) -> PResult<Option<PermutationBuffer>> {
+        Self::new(nb_elems).map(|dg| dg.permutation(idx))
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for Derangements {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        Ok(nb_partial_derangements(self.nb_elems, self.nb_elems))
+    }
+
+    fn unrank(&self, mut idx: R, buffer: &mut PermutationBuffer) {
+        let mut remaining = (1u64 << self.nb_elems) - 1;
+        for pos in 0..self.nb_elems {
+            for elem in
+                (0..self.nb_elems).filter(|&elem| elem != pos && remaining & (1 << elem) != 0)
+            {
+                let nb = self.nb_completions::<R>(pos, remaining & !(1 << elem));
+                if idx < nb {
+                    buffer.push(elem);
+                    remaining &= !(1 << elem);
+                    break;
+                }
+                idx -= nb;
+            }
+        }
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        let mut rank = R::ZERO;
+        let mut pos = 0;
+        walk_permutation(self.nb_elems, perm, |elem, remaining| {
+            if elem == pos {
+                return Err(PermutationGeneratorError::NotInFamily);
+            }
+            for smaller in
+                (0..elem).filter(|&smaller| smaller != pos && remaining & (1 << smaller) != 0)
+            {
+                rank += self.nb_completions::<R>(pos, remaining & !(1 << smaller));
+            }
+            pos += 1;
+            Ok(())
+        })
+        .map(|_| rank)
+    }
+}
+
+impl Derangements {
+    /// The number of derangements completing a prefix upto `pos`, leaving the elements in `remaining`.
+    #[inline]
+    fn nb_completions<R: Rank>(&self, pos: u8, remaining: u64) -> R {
+        let still_constrained = remaining >> (pos + 1);
+        nb_partial_derangements(
+            self.nb_elems - pos - 1,
+            still_constrained.count_ones() as u8,
+        )
+    }
+}
+
+/// The number of arrangements of `nb_remaining` elements, `nb_constrained` of which each forbid one distinct position.
+///
+/// The inclusion-exclusion terms decrease, so the partial sums stay between `0` and `nb_remaining!`.
+pub(crate) fn nb_partial_derangements<R: Rank>(nb_remaining: u8, nb_constrained: u8) -> R {
+    let mut term = nb_arrangements::<R>(nb_remaining);
+    let mut total = R::ZERO;
+    for j in 0..=nb_constrained {
+        if j % 2 == 0 {
+            total += term;
+        } else {
+            total -= term;
+        }
+        if j < nb_constrained {
+            term = term / R::from(nb_remaining - j) * R::from(nb_constrained - j) / R::from(j + 1);
+        }
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            14833,
+            DerangementGenerator8::new(8).unwrap().nb_permutations()
+        );
+        assert_eq!(
+            7697064251745,
+            DerangementGenerator16::new(16).unwrap().nb_permutations()
+        );
+        assert_eq!(
+            96800425246141091510518408809597121,
+            DerangementGenerator32::new(32).unwrap().nb_permutations()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            DerangementGenerator8::new(9).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn zero_and_one() {
+        assert!(DerangementGenerator8::new(0)
+            .unwrap()
+            .next_permutation()
+            .is_none());
+        assert!(DerangementGenerator8::new(1)
+            .unwrap()
+            .next_permutation()
+            .is_none());
+    }
+
+    #[test]
+    fn next_permutation() {
+        let mut dg = DerangementGenerator8::new(4).unwrap();
+        test_slice(&[1, 0, 3, 2], dg.next_permutation());
+        test_slice(&[1, 2, 3, 0], dg.next_permutation());
+        test_slice(&[3, 2, 1, 0], dg.nth(6));
+        assert!(dg.next_permutation().is_none());
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        let dg = DerangementGenerator8::new(6).unwrap();
+        let members = assert_matches_filtered(&dg, |perm| {
+            perm.iter()
+                .enumerate()
+                .all(|(pos, &elem)| pos != elem as usize)
+        });
+        assert!(members.is_sorted());
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[1, 0, 3, 2, 5, 4, 7, 6, 9, 8],
+            DerangementGenerator16::nth_absolute(10, 0).unwrap(),
+        );
+        test_slice(
+            &[9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+            DerangementGenerator16::nth_absolute(10, 1334960).unwrap(),
+        );
+        assert!(DerangementGenerator16::nth_absolute(10, 1334961)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rank() {
+        let dg = DerangementGenerator32::new(30).unwrap();
+        let idx = 1234567890123456789012345678901;
+        let perm = dg.permutation(idx).unwrap();
+        assert_eq!(Ok(idx), dg.rank(&perm));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            DerangementGenerator8::new(3).unwrap().rank(&[1, 0, 2])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            DerangementGenerator8::new(3).unwrap().rank(&[1, 1, 0])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::LengthMismatch),
+            DerangementGenerator8::new(3).unwrap().rank(&[1, 0])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            DerangementGenerator8::new(0).unwrap().rank(&[])
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use super::*;
+
+/// Permutations with exactly `nb_descents` descents, `p[i] > p[i + 1]`, counted by the Eulerian numbers.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EulerianPermutations {
+    nb_elems: u8,
+    nb_descents: u8,
+    eulerian_numbers: Vec<[[u128; 33]; 33]>,
+}
+
+pub type EulerianPermutationGenerator<R> = FamilyGenerator<EulerianPermutations, R>;
+pub type EulerianPermutationGenerator8 = EulerianPermutationGenerator<u16>;
+pub type EulerianPermutationGenerator16 = EulerianPermutationGenerator<u64>;
+pub type EulerianPermutationGenerator32 = EulerianPermutationGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<EulerianPermutations, R> {
+    pub fn new(nb_elems: u8, nb_descents: u8) -> PResult<Self> {
+        EulerianPermutations::new(nb_elems, nb_descents).and_then(Self::from_family)
+    }
+
+    pub fn nth_absolute(
+        nb_elems: u8,
+        nb_descents: u8,
+        idx: R,
+    ) -> PResult<Option<PermutationBuffer>> {
+        Self::new(nb_elems, nb_descents).map(|eg| eg.permutation(idx))
+    }
+}
+
+impl EulerianPermutations {
+    fn new(nb_elems: u8, nb_descents: u8) -> PResult<Self> {
+        if nb_elems > u128::MAX_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        let mut family = Self {
+            nb_elems,
+            nb_descents,
+            eulerian_numbers: Vec::new(),
+        };
+        if family.is_reachable() {
+            family.eulerian_numbers = family.eulerian_numbers();
+        }
+        Ok(family)
+    }
+
+    /// Whether `nb_descents` is below `nb_elems`, the decreasing permutation having `nb_elems - 1` of them.
+    #[inline]
+    fn is_reachable(&self) -> bool {
+        self.nb_descents < self.nb_elems
+    }
+
+    /// `table[j][r][b]`, the number of arrangements of `r` values after a value above `b` of them with `j` descents,
+    /// counting the step from that value, for `j <= nb_descents`.
+    fn eulerian_numbers(&self) -> Vec<[[u128; 33]; 33]> {
+        let mut table = vec![[[0; 33]; 33]; self.nb_descents as usize + 1];
+        table[0][0][0] = 1;
+        for nb_remaining in 1..self.nb_elems as usize {
+            for descents in 0..=self.nb_descents as usize {
+                for nb_below in 0..=nb_remaining {
+                    let down = match descents.checked_sub(1) {
+                        Some(descents) => {
+                            table[descents][nb_remaining - 1][..nb_below].iter().sum()
+                        }
+                        None => 0,
+                    };
+                    let up: u128 = table[descents][nb_remaining - 1][nb_below..nb_remaining]
+                        .iter()
+                        .sum();
+                    table[descents][nb_remaining][nb_below] = down + up;
+                }
+            }
+        }
+        table
+    }
+
+    /// The number of completions after placing `elem` with `descents_left` descents still to come, if any.
+    #[inline]
+    fn nb_completions<R: Rank>(
+        &self,
+        pos: u8,
+        elem: u8,
+        remaining: u64,
+        descents_left: Option<u8>,
+    ) -> R {
+        let nb_below = (remaining & ((1 << elem) - 1)).count_ones() as usize;
+        descents_left.map_or(R::ZERO, |descents| {
+            R::from_u128(
+                self.eulerian_numbers[descents as usize][(self.nb_elems - 1 - pos) as usize]
+                    [nb_below],
+            )
+            .unwrap()
+        })
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for EulerianPermutations {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        if !self.is_reachable() {
+            return Ok(R::ZERO);
+        }
+        let nb_after_first = self.nb_elems as usize - 1;
+        let count = self.eulerian_numbers[self.nb_descents as usize][nb_after_first]
+            [..=nb_after_first]
+            .iter()
+            .sum();
+        R::from_u128(count).ok_or(PermutationGeneratorError::TooManyElements)
+    }
+
+    fn unrank(&self, mut idx: R, buffer: &mut PermutationBuffer) {
+        let mut remaining = (1u64 << self.nb_elems) - 1;
+        let mut descents_left = self.nb_descents;
+        let mut previous = None;
+        for pos in 0..self.nb_elems {
+            for elem in (0..self.nb_elems).filter(|&elem| remaining & (1 << elem) != 0) {
+                let is_descent = previous.is_some_and(|previous| elem < previous);
+                let left = descents_left.checked_sub(is_descent as u8);
+                let nb = self.nb_completions(pos, elem, remaining, left);
+                if idx < nb {
+                    buffer.push(elem);
+                    remaining &= !(1 << elem);
+                    descents_left -= is_descent as u8;
+                    previous = Some(elem);
+                    break;
+                }
+                idx -= nb;
+            }
+        }
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        if !self.is_reachable() {
+            return check_permutation(perm).and(Err(PermutationGeneratorError::NotInFamily));
+        }
+        let mut rank = R::ZERO;
+        let mut pos = 0;
+        let mut descents_left = Some(self.nb_descents);
+        let mut previous: Option<u8> = None;
+        walk_permutation(self.nb_elems, perm, |elem, remaining| {
+            let left = |elem: u8| {
+                let is_descent = previous.is_some_and(|previous| elem < previous);
+                descents_left.and_then(|descents| descents.checked_sub(is_descent as u8))
+            };
+            for smaller in (0..elem).filter(|&smaller| remaining & (1 << smaller) != 0) {
+                rank += self.nb_completions(pos, smaller, remaining, left(smaller));
+            }
+            descents_left = left(elem);
+            previous = Some(elem);
+            pos += 1;
+            Ok(())
+        })?;
+        if descents_left == Some(0) {
+            Ok(rank)
+        } else {
+            Err(PermutationGeneratorError::NotInFamily)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    fn nb_descents(perm: &[u8]) -> u8 {
+        perm.windows(2).filter(|pair| pair[0] > pair[1]).count() as u8
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            15619,
+            EulerianPermutationGenerator8::new(8, 3)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            1,
+            EulerianPermutationGenerator16::new(16, 15)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            65519,
+            EulerianPermutationGenerator16::new(16, 1)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            0,
+            EulerianPermutationGenerator16::new(16, 16)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            EulerianPermutationGenerator32::new(33, 1).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn next_permutation() {
+        let mut eg = EulerianPermutationGenerator8::new(3, 1).unwrap();
+        test_slice(&[0, 2, 1], eg.next_permutation());
+        test_slice(&[1, 0, 2], eg.next_permutation());
+        test_slice(&[2, 0, 1], eg.nth(1));
+        assert!(eg.next_permutation().is_none());
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        let mut total = 0;
+        for descents in 0..=7 {
+            let eg = EulerianPermutationGenerator8::new(7, descents).unwrap();
+            let members = assert_matches_filtered(&eg, |perm| nb_descents(perm) == descents);
+            assert!(members.is_sorted());
+            total += members.len();
+        }
+        assert_eq!(5040, total);
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[0, 1, 2, 3, 4, 5, 6, 7, 9, 8],
+            EulerianPermutationGenerator16::nth_absolute(10, 1, 0).unwrap(),
+        );
+        test_slice(
+            &[9, 0, 1, 2, 3, 4, 5, 6, 7, 8],
+            EulerianPermutationGenerator16::nth_absolute(10, 1, 1012).unwrap(),
+        );
+        assert!(EulerianPermutationGenerator16::nth_absolute(10, 1, 1013)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rank() {
+        let eg = EulerianPermutationGenerator32::new(32, 12).unwrap();
+        let idx = 123456789012345678901234567;
+        let perm = eg.permutation(idx).unwrap();
+        assert_eq!(12, nb_descents(&perm));
+        assert_eq!(Ok(idx), eg.rank(&perm));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            EulerianPermutationGenerator8::new(4, 1)
+                .unwrap()
+                .rank(&[3, 2, 0, 1])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            EulerianPermutationGenerator8::new(4, 1)
+                .unwrap()
+                .rank(&[0, 1, 2, 3])
+        );
+    }
+}
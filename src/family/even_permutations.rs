@@ -0,0 +1,139 @@
+use super::*;
+
+/// The even permutations, forming the alternating group of `n!/2` members.
+///
+/// In lexicographic order, permutations `2k` and `2k + 1` only differ by swapping their last two elements,
+/// so exactly one of both is even: the `k`-th even permutation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EvenPermutations {
+    nb_elems: u8,
+}
+
+pub type EvenPermutationGenerator<R> = FamilyGenerator<EvenPermutations, R>;
+pub type EvenPermutationGenerator8 = EvenPermutationGenerator<u16>;
+pub type EvenPermutationGenerator16 = EvenPermutationGenerator<u64>;
+pub type EvenPermutationGenerator32 = EvenPermutationGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<EvenPermutations, R> {
+    pub fn new(nb_elems: u8) -> PResult<Self> {
+        Self::from_family(EvenPermutations { nb_elems })
+    }
+
+    pub fn nth_absolute(nb_elems: u8, idx: R) -> PResult<Option<PermutationBuffer>> {
+        Self::new(nb_elems).map(|eg| eg.permutation(idx))
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for EvenPermutations {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        Ok(match self.nb_elems {
+            0..=1 => R::ONE,
+            nb_elems => R::factorial(nb_elems) / R::from(2),
+        })
+    }
+
+    fn unrank(&self, idx: R, buffer: &mut PermutationBuffer) {
+        let nb_perms = R::factorial(self.nb_elems);
+        R::permutation(self.nb_elems, nb_perms, idx * R::from(2))
+            .into_iter()
+            .flatten()
+            .for_each(|elem| buffer.push(elem));
+        if is_odd(buffer) {
+            let len = buffer.len();
+            buffer.swap(len - 2, len - 1);
+        }
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        let rank = lehmer_rank::<R>(self.nb_elems, perm.iter().copied())?;
+        if is_odd(perm) {
+            Err(PermutationGeneratorError::NotInFamily)
+        } else {
+            Ok(rank / R::from(2))
+        }
+    }
+}
+
+/// Whether the permutation of `0..perm.len()` has an odd number of inversions.
+#[inline]
+pub(crate) fn is_odd(perm: &[u8]) -> bool {
+    let mut remaining = u64::MAX;
+    let mut nb_inversions = 0;
+    for &elem in perm {
+        nb_inversions += (remaining & ((1 << elem) - 1)).count_ones();
+        remaining &= !(1 << elem);
+    }
+    nb_inversions % 2 == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            20160,
+            EvenPermutationGenerator8::new(8).unwrap().nb_permutations()
+        );
+        assert_eq!(
+            factorial128(32) / 2,
+            EvenPermutationGenerator32::new(32)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(1, EvenPermutationGenerator8::new(1).unwrap().count());
+        assert_eq!(0, EvenPermutationGenerator8::new(0).unwrap().count());
+    }
+
+    #[test]
+    fn next_permutation() {
+        let mut eg = EvenPermutationGenerator8::new(3).unwrap();
+        test_slice(&[0, 1, 2], eg.next_permutation());
+        test_slice(&[1, 2, 0], eg.next_permutation());
+        test_slice(&[2, 0, 1], eg.next_permutation());
+        assert!(eg.next_permutation().is_none());
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        let eg = EvenPermutationGenerator8::new(6).unwrap();
+        let members = assert_matches_filtered(&eg, |perm| !is_odd(perm));
+        assert_eq!(360, members.len());
+        assert!(members.is_sorted());
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+            EvenPermutationGenerator16::nth_absolute(16, factorial64(16) / 2 - 1).unwrap(),
+        );
+        test_slice(
+            &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 14, 15, 13],
+            EvenPermutationGenerator16::nth_absolute(16, 1).unwrap(),
+        );
+    }
+
+    #[test]
+    fn rank() {
+        let eg = EvenPermutationGenerator8::new(4).unwrap();
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            eg.rank(&[1, 0, 2, 3])
+        );
+        assert_eq!(Ok(11), eg.rank(&[3, 2, 1, 0]));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            eg.rank(&[0, 0, 2, 3])
+        );
+    }
+}
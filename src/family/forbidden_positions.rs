@@ -0,0 +1,165 @@
+use super::*;
+
+/// Permutations avoiding forbidden placements, e.g. assignments of workers to slots where some pairs are excluded.
+///
+/// `forbidden[elem][pos]` forbids `elem` at position `pos`. As for `LinearExtensions`, `nb_elems` is limited to
+/// `MAX_SUBSET_DP_ELEMENTS`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ForbiddenPositions {
+    nb_elems: u8,
+    forbidden: [u32; 32],
+    subset_counts: SubsetCounts,
+}
+
+pub type ForbiddenPositionGenerator<R> = FamilyGenerator<ForbiddenPositions, R>;
+pub type ForbiddenPositionGenerator8 = ForbiddenPositionGenerator<u16>;
+pub type ForbiddenPositionGenerator16 = ForbiddenPositionGenerator<u64>;
+pub type ForbiddenPositionGenerator32 = ForbiddenPositionGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<ForbiddenPositions, R> {
+    /// `LengthMismatch` if `forbidden` is not a square matrix.
+    pub fn new(forbidden: &[&[bool]]) -> PResult<Self> {
+        ForbiddenPositions::new(forbidden).and_then(Self::from_family)
+    }
+
+    pub fn nth_absolute(forbidden: &[&[bool]], idx: R) -> PResult<Option<PermutationBuffer>> {
+        Self::new(forbidden).map(|fg| fg.permutation(idx))
+    }
+}
+
+impl ForbiddenPositions {
+    fn new(forbidden: &[&[bool]]) -> PResult<Self> {
+        if forbidden.len() > MAX_SUBSET_DP_ELEMENTS as usize {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        let nb_elems = forbidden.len() as u8;
+        let mut forbidden_masks = [0; 32];
+        for (mask, row) in forbidden_masks.iter_mut().zip(forbidden) {
+            if row.len() != forbidden.len() {
+                return Err(PermutationGeneratorError::LengthMismatch);
+            }
+            *mask = row
+                .iter()
+                .rev()
+                .fold(0, |mask, &is_forbidden| mask << 1 | is_forbidden as u32);
+        }
+        SubsetCounts::new(nb_elems, |placed, elem| {
+            Self::is_allowed(&forbidden_masks, placed, elem)
+        })
+        .map(|subset_counts| Self {
+            nb_elems,
+            forbidden: forbidden_masks,
+            subset_counts,
+        })
+    }
+
+    /// Whether `elem` may take the position right after the elements of `placed`.
+    #[inline]
+    fn is_allowed(forbidden: &[u32; 32], placed: BitIndex32, elem: u8) -> bool {
+        forbidden[elem as usize] & (1 << placed.nb_elements()) == 0
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for ForbiddenPositions {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        self.subset_counts.count()
+    }
+
+    fn unrank(&self, idx: R, buffer: &mut PermutationBuffer) {
+        self.subset_counts.unrank(idx, buffer, |placed, elem| {
+            Self::is_allowed(&self.forbidden, placed, elem)
+        })
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        self.subset_counts.rank(perm, |placed, elem| {
+            Self::is_allowed(&self.forbidden, placed, elem)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    fn matrix(nb_elems: usize, is_forbidden: impl Fn(usize, usize) -> bool) -> Vec<Vec<bool>> {
+        (0..nb_elems)
+            .map(|elem| (0..nb_elems).map(|pos| is_forbidden(elem, pos)).collect())
+            .collect()
+    }
+
+    fn rows(matrix: &[Vec<bool>]) -> Vec<&[bool]> {
+        matrix.iter().map(Vec::as_slice).collect()
+    }
+
+    #[test]
+    fn new() {
+        let diagonal = matrix(8, |elem, pos| elem == pos);
+        assert_eq!(
+            14833,
+            ForbiddenPositionGenerator8::new(&rows(&diagonal))
+                .unwrap()
+                .nb_permutations()
+        );
+        let menage = matrix(20, |elem, pos| elem == pos || (elem + 1) % 20 == pos);
+        assert_eq!(
+            312400218671253762,
+            ForbiddenPositionGenerator32::new(&rows(&menage))
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            PermutationGeneratorError::LengthMismatch,
+            ForbiddenPositionGenerator8::new(&[&[false, false], &[false]]).unwrap_err()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            ForbiddenPositionGenerator32::new(&rows(&matrix(21, |_, _| false))).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn next_permutation() {
+        let forbidden = matrix(3, |elem, pos| elem == 0 && pos == 0);
+        let mut fg = ForbiddenPositionGenerator8::new(&rows(&forbidden)).unwrap();
+        test_slice(&[1, 0, 2], fg.next_permutation());
+        test_slice(&[1, 2, 0], fg.next_permutation());
+        test_slice(&[2, 1, 0], fg.nth(1));
+        assert!(fg.next_permutation().is_none());
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        let forbidden = matrix(7, |elem, pos| {
+            (elem * 3 + pos * 5) % 7 == 1 || elem + pos == 6
+        });
+        let fg = ForbiddenPositionGenerator8::new(&rows(&forbidden)).unwrap();
+        let members = assert_matches_filtered(&fg, |perm| {
+            perm.iter()
+                .enumerate()
+                .all(|(pos, &elem)| !forbidden[elem as usize][pos])
+        });
+        assert!(members.is_sorted());
+    }
+
+    #[test]
+    fn rank() {
+        let forbidden = matrix(18, |elem, pos| (elem + pos) % 4 == 0);
+        let fg = ForbiddenPositionGenerator32::new(&rows(&forbidden)).unwrap();
+        let idx = fg.nb_permutations() / 3;
+        let perm = fg.permutation(idx).unwrap();
+        assert_eq!(Ok(idx), fg.rank(&perm));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            fg.rank(&(0..18).collect::<Vec<_>>())
+        );
+    }
+}
@@ -0,0 +1,192 @@
+use super::*;
+
+/// Permutations that are their own inverse: pairings with fixed points, counted by the telephone numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Involutions {
+    nb_elems: u8,
+    telephone_numbers: [u128; 33],
+}
+
+pub type InvolutionGenerator<R> = FamilyGenerator<Involutions, R>;
+pub type InvolutionGenerator8 = InvolutionGenerator<u16>;
+pub type InvolutionGenerator16 = InvolutionGenerator<u64>;
+pub type InvolutionGenerator32 = InvolutionGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<Involutions, R> {
+    pub fn new(nb_elems: u8) -> PResult<Self> {
+        Involutions::new(nb_elems).and_then(Self::from_family)
+    }
+
+    pub fn nth_absolute(nb_elems: u8, idx: R) -> PResult<Option<PermutationBuffer>> {
+        Self::new(nb_elems).map(|ig| ig.permutation(idx))
+    }
+}
+
+impl Involutions {
+    fn new(nb_elems: u8) -> PResult<Self> {
+        if nb_elems > u128::MAX_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        Ok(Self {
+            nb_elems,
+            telephone_numbers: telephone_numbers(nb_elems),
+        })
+    }
+
+    #[inline]
+    fn telephone<R: Rank>(&self, nb_elems: usize) -> R {
+        R::from_u128(self.telephone_numbers[nb_elems]).unwrap()
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for Involutions {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        R::from_u128(self.telephone_numbers[self.nb_elems as usize])
+            .ok_or(PermutationGeneratorError::TooManyElements)
+    }
+
+    /// At the first position not paired yet, a fixed point sorts before pairing it with any later free position.
+    fn unrank(&self, mut idx: R, buffer: &mut PermutationBuffer) {
+        let mut perm = [0; 32];
+        let mut free = (1u64 << self.nb_elems) - 1;
+        for pos in 0..self.nb_elems {
+            if free & (1 << pos) == 0 {
+                continue;
+            }
+            free &= !(1 << pos);
+            let nb_free = free.count_ones() as usize;
+            perm[pos as usize] = pos;
+            if idx < self.telephone(nb_free) {
+                continue;
+            }
+            idx -= self.telephone(nb_free);
+            let partner_nb = (idx / self.telephone(nb_free - 1)).as_u8();
+            idx -= R::from(partner_nb) * self.telephone(nb_free - 1);
+            let partner = (0..self.nb_elems)
+                .filter(|&elem| free & (1 << elem) != 0)
+                .nth(partner_nb as usize)
+                .unwrap();
+            free &= !(1 << partner);
+            perm[pos as usize] = partner;
+            perm[partner as usize] = pos;
+        }
+        perm[..self.nb_elems as usize]
+            .iter()
+            .for_each(|&elem| buffer.push(elem));
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        check_permutation(perm)?;
+        if perm
+            .iter()
+            .enumerate()
+            .any(|(pos, &elem)| perm[elem as usize] as usize != pos)
+        {
+            return Err(PermutationGeneratorError::NotInFamily);
+        }
+        let mut free = (1u64 << self.nb_elems) - 1;
+        let mut rank = R::ZERO;
+        for (pos, &elem) in (0..self.nb_elems).zip(perm) {
+            if free & (1 << pos) == 0 {
+                continue;
+            }
+            free &= !(1 << pos);
+            let nb_free = free.count_ones() as usize;
+            if elem != pos {
+                let partner_nb = (free & ((1 << elem) - 1)).count_ones() as u8;
+                rank += self.telephone::<R>(nb_free)
+                    + R::from(partner_nb) * self.telephone::<R>(nb_free - 1);
+                free &= !(1 << elem);
+            }
+        }
+        Ok(rank)
+    }
+}
+
+/// The telephone numbers `T(0..=nb_elems)`, with `T(n) = T(n-1) + (n-1) T(n-2)`.
+fn telephone_numbers(nb_elems: u8) -> [u128; 33] {
+    let mut telephone = [1; 33];
+    for n in 2..=nb_elems as usize {
+        telephone[n] = telephone[n - 1] + (n as u128 - 1) * telephone[n - 2];
+    }
+    telephone
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(764, InvolutionGenerator8::new(8).unwrap().nb_permutations());
+        assert_eq!(
+            46206736,
+            InvolutionGenerator16::new(16).unwrap().nb_permutations()
+        );
+        assert_eq!(
+            22481059424730751232,
+            InvolutionGenerator32::new(32).unwrap().nb_permutations()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            InvolutionGenerator32::new(33).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn iter() {
+        let perms = InvolutionGenerator8::new(3)
+            .unwrap()
+            .map(|perm| perm.collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![vec![0, 1, 2], vec![0, 2, 1], vec![1, 0, 2], vec![2, 1, 0]],
+            perms
+        );
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        let ig = InvolutionGenerator8::new(7).unwrap();
+        let members = assert_matches_filtered(&ig, |perm| {
+            perm.iter()
+                .enumerate()
+                .all(|(pos, &elem)| perm[elem as usize] as usize == pos)
+        });
+        assert_eq!(232, members.len());
+        assert!(members.is_sorted());
+    }
+
+    #[test]
+    fn permutation_into() {
+        let ig = InvolutionGenerator16::new(10).unwrap();
+        let mut buffer = PermutationBuffer::new();
+        assert!(ig.permutation_into(ig.nb_permutations() - 1, &mut buffer));
+        assert_eq!(&[9, 8, 7, 6, 5, 4, 3, 2, 1, 0], buffer.as_slice());
+        assert!(!ig.permutation_into(ig.nb_permutations(), &mut buffer));
+        assert_eq!(&[9, 8, 7, 6, 5, 4, 3, 2, 1, 0], buffer.as_slice());
+        test_slice(
+            &[0, 1, 2, 3, 4, 5, 6, 7, 9, 8],
+            InvolutionGenerator16::nth_absolute(10, 1).unwrap(),
+        );
+    }
+
+    #[test]
+    fn rank() {
+        let ig = InvolutionGenerator32::new(32).unwrap();
+        let idx = 12345678901234567890;
+        assert_eq!(Ok(idx), ig.rank(&ig.permutation(idx).unwrap()));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            InvolutionGenerator8::new(3).unwrap().rank(&[1, 2, 0])
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use super::*;
+
+/// Permutations respecting precedence constraints, the topological orders of a DAG over `0..nb_elems`.
+///
+/// Linear extensions are counted with a table over the subsets of placed elements, so `nb_elems` is limited to
+/// `MAX_SUBSET_DP_ELEMENTS`. Constraints with a cycle leave no linear extensions.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LinearExtensions {
+    nb_elems: u8,
+    predecessors: [u32; 32],
+    subset_counts: SubsetCounts,
+}
+
+pub type LinearExtensionGenerator<R> = FamilyGenerator<LinearExtensions, R>;
+pub type LinearExtensionGenerator8 = LinearExtensionGenerator<u16>;
+pub type LinearExtensionGenerator16 = LinearExtensionGenerator<u64>;
+pub type LinearExtensionGenerator32 = LinearExtensionGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<LinearExtensions, R> {
+    /// Each `(before, after)` pair requires `before` to come before `after`.
+    pub fn new(nb_elems: u8, precedences: &[(u8, u8)]) -> PResult<Self> {
+        LinearExtensions::new(nb_elems, precedences).and_then(Self::from_family)
+    }
+
+    pub fn nth_absolute(
+        nb_elems: u8,
+        precedences: &[(u8, u8)],
+        idx: R,
+    ) -> PResult<Option<PermutationBuffer>> {
+        Self::new(nb_elems, precedences).map(|lg| lg.permutation(idx))
+    }
+}
+
+impl LinearExtensions {
+    fn new(nb_elems: u8, precedences: &[(u8, u8)]) -> PResult<Self> {
+        if nb_elems > MAX_SUBSET_DP_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        let mut predecessors = [0; 32];
+        for &(before, after) in precedences {
+            if before >= nb_elems || after >= nb_elems {
+                return Err(PermutationGeneratorError::NotAPermutation);
+            }
+            predecessors[after as usize] |= 1 << before;
+        }
+        SubsetCounts::new(nb_elems, |placed, elem| {
+            Self::has_predecessors_placed(&predecessors, placed, elem)
+        })
+        .map(|subset_counts| Self {
+            nb_elems,
+            predecessors,
+            subset_counts,
+        })
+    }
+
+    #[inline]
+    fn has_predecessors_placed(predecessors: &[u32; 32], placed: BitIndex32, elem: u8) -> bool {
+        predecessors[elem as usize] & !placed.unwrap() == 0
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for LinearExtensions {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        self.subset_counts.count()
+    }
+
+    fn unrank(&self, idx: R, buffer: &mut PermutationBuffer) {
+        self.subset_counts.unrank(idx, buffer, |placed, elem| {
+            Self::has_predecessors_placed(&self.predecessors, placed, elem)
+        })
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        self.subset_counts.rank(perm, |placed, elem| {
+            Self::has_predecessors_placed(&self.predecessors, placed, elem)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            40320,
+            LinearExtensionGenerator8::new(8, &[])
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            1,
+            LinearExtensionGenerator8::new(4, &[(0, 1), (1, 2), (2, 3)])
+                .unwrap()
+                .count()
+        );
+        assert_eq!(
+            0,
+            LinearExtensionGenerator8::new(3, &[(0, 1), (1, 2), (2, 0)])
+                .unwrap()
+                .count()
+        );
+        assert_eq!(
+            PermutationGeneratorError::NotAPermutation,
+            LinearExtensionGenerator8::new(3, &[(0, 3)]).unwrap_err()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            LinearExtensionGenerator32::new(21, &[]).unwrap_err()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            LinearExtensionGenerator8::new(9, &[(0, 1)]).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn iter() {
+        let perms = LinearExtensionGenerator8::new(4, &[(0, 1), (2, 3)])
+            .unwrap()
+            .map(|perm| perm.collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                vec![0, 1, 2, 3],
+                vec![0, 2, 1, 3],
+                vec![0, 2, 3, 1],
+                vec![2, 0, 1, 3],
+                vec![2, 0, 3, 1],
+                vec![2, 3, 0, 1],
+            ],
+            perms
+        );
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        let precedences = [(0, 3), (1, 3), (3, 5), (2, 6), (4, 6)];
+        let lg = LinearExtensionGenerator8::new(7, &precedences).unwrap();
+        let members = assert_matches_filtered(&lg, |perm| {
+            let pos = |elem| perm.iter().position(|&e| e == elem).unwrap();
+            precedences
+                .iter()
+                .all(|&(before, after)| pos(before) < pos(after))
+        });
+        assert!(members.is_sorted());
+    }
+
+    #[test]
+    fn nth_absolute() {
+        let chains = [(0, 1), (1, 2), (3, 4), (4, 5), (6, 7), (7, 8)];
+        test_slice(
+            &[6, 7, 8, 3, 4, 5, 0, 1, 2],
+            LinearExtensionGenerator16::nth_absolute(9, &chains, 1679).unwrap(),
+        );
+        assert!(LinearExtensionGenerator16::nth_absolute(9, &chains, 1680)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rank() {
+        let precedences = (0..19)
+            .map(|elem| (elem, elem + 1))
+            .step_by(3)
+            .collect::<Vec<_>>();
+        let lg = LinearExtensionGenerator32::new(20, &precedences).unwrap();
+        let idx = 123456789012345;
+        let perm = lg.permutation(idx).unwrap();
+        assert_eq!(Ok(idx), lg.rank(&perm));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            LinearExtensionGenerator8::new(3, &[(2, 0)])
+                .unwrap()
+                .rank(&[0, 1, 2])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            LinearExtensionGenerator8::new(3, &[(2, 0)])
+                .unwrap()
+                .rank(&[2, 2, 0])
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use super::*;
+
+/// Permutations with exactly `nb_inversions` inversions, counted by the Mahonian numbers.
+///
+/// The Lehmer digits decoded by `PermutationGenerator`, the number of smaller elements still remaining at each
+/// position, sum to the number of inversions. Ranking within the class only restricts the digits to that sum,
+/// so the lexicographic order is kept.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MahonianPermutations {
+    nb_elems: u8,
+    nb_inversions: u16,
+    mahonian_numbers: Vec<[u128; 33]>,
+}
+
+pub type MahonianPermutationGenerator<R> = FamilyGenerator<MahonianPermutations, R>;
+pub type MahonianPermutationGenerator8 = MahonianPermutationGenerator<u16>;
+pub type MahonianPermutationGenerator16 = MahonianPermutationGenerator<u64>;
+pub type MahonianPermutationGenerator32 = MahonianPermutationGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<MahonianPermutations, R> {
+    pub fn new(nb_elems: u8, nb_inversions: u16) -> PResult<Self> {
+        MahonianPermutations::new(nb_elems, nb_inversions).and_then(Self::from_family)
+    }
+
+    pub fn nth_absolute(
+        nb_elems: u8,
+        nb_inversions: u16,
+        idx: R,
+    ) -> PResult<Option<PermutationBuffer>> {
+        Self::new(nb_elems, nb_inversions).map(|mg| mg.permutation(idx))
+    }
+}
+
+impl MahonianPermutations {
+    fn new(nb_elems: u8, nb_inversions: u16) -> PResult<Self> {
+        if nb_elems > u128::MAX_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        let mut family = Self {
+            nb_elems,
+            nb_inversions,
+            mahonian_numbers: Vec::new(),
+        };
+        if family.is_reachable() {
+            family.mahonian_numbers = family.mahonian_numbers();
+        }
+        Ok(family)
+    }
+
+    /// Whether `nb_inversions` is at most `n(n-1)/2`, reached by the decreasing permutation only.
+    #[inline]
+    fn is_reachable(&self) -> bool {
+        let nb_elems = self.nb_elems as u16;
+        self.nb_inversions <= nb_elems * nb_elems.saturating_sub(1) / 2
+    }
+
+    /// `table[k][r]`, the number of Lehmer codes of `r` remaining digits summing to `k <= nb_inversions`.
+    fn mahonian_numbers(&self) -> Vec<[u128; 33]> {
+        let nb_inversions = self.nb_inversions as usize;
+        let mut table = vec![[0; 33]; nb_inversions + 1];
+        table[0][0] = 1;
+        for nb_remaining in 1..=self.nb_elems as usize {
+            for inversions in 0..=nb_inversions {
+                table[inversions][nb_remaining] = (0..nb_remaining.min(inversions + 1))
+                    .map(|digit| table[inversions - digit][nb_remaining - 1])
+                    .sum();
+            }
+        }
+        table
+    }
+
+    #[inline]
+    fn mahonian<R: Rank>(&self, inversions: usize, nb_remaining: usize) -> R {
+        R::from_u128(self.mahonian_numbers[inversions][nb_remaining]).unwrap()
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for MahonianPermutations {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        if !self.is_reachable() {
+            return Ok(R::ZERO);
+        }
+        R::from_u128(self.mahonian_numbers[self.nb_inversions as usize][self.nb_elems as usize])
+            .ok_or(PermutationGeneratorError::TooManyElements)
+    }
+
+    fn unrank(&self, mut idx: R, buffer: &mut PermutationBuffer) {
+        let mut inversions = self.nb_inversions as usize;
+        let mut lehmer_rank = R::ZERO;
+        for nb_remaining in (1..=self.nb_elems as usize).rev() {
+            let mut digit = 0;
+            while idx >= self.mahonian(inversions - digit, nb_remaining - 1) {
+                idx -= self.mahonian(inversions - digit, nb_remaining - 1);
+                digit += 1;
+            }
+            inversions -= digit;
+            lehmer_rank = lehmer_rank * R::from(nb_remaining as u8) + R::from(digit as u8);
+        }
+        R::permutation(self.nb_elems, R::factorial(self.nb_elems), lehmer_rank)
+            .into_iter()
+            .flatten()
+            .for_each(|elem| buffer.push(elem));
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        if !self.is_reachable() {
+            return check_permutation(perm).and(Err(PermutationGeneratorError::NotInFamily));
+        }
+        let mut inversions = self.nb_inversions as usize;
+        let mut rank = R::ZERO;
+        walk_permutation(self.nb_elems, perm, |elem, remaining| {
+            let nb_remaining = remaining.count_ones() as usize;
+            let digit = (remaining & ((1 << elem) - 1)).count_ones() as usize;
+            if digit > inversions {
+                return Err(PermutationGeneratorError::NotInFamily);
+            }
+            for smaller_digit in 0..digit {
+                rank += self.mahonian::<R>(inversions - smaller_digit, nb_remaining - 1);
+            }
+            inversions -= digit;
+            Ok(())
+        })?;
+        if inversions == 0 {
+            Ok(rank)
+        } else {
+            Err(PermutationGeneratorError::NotInFamily)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    fn nb_inversions(perm: &[u8]) -> u16 {
+        (0..perm.len())
+            .map(|i| (i..perm.len()).filter(|&j| perm[j] < perm[i]).count() as u16)
+            .sum()
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            3,
+            MahonianPermutationGenerator8::new(4, 1)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            3836,
+            MahonianPermutationGenerator8::new(8, 14)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            1,
+            MahonianPermutationGenerator16::new(16, 120)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            0,
+            MahonianPermutationGenerator16::new(16, 121)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            MahonianPermutationGenerator8::new(3, u16::MAX)
+                .unwrap()
+                .rank(&[2, 1, 0])
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            MahonianPermutationGenerator32::new(33, 1).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn next_permutation() {
+        let mut mg = MahonianPermutationGenerator8::new(4, 1).unwrap();
+        test_slice(&[0, 1, 3, 2], mg.next_permutation());
+        test_slice(&[0, 2, 1, 3], mg.next_permutation());
+        test_slice(&[1, 0, 2, 3], mg.next_permutation());
+        assert!(mg.next_permutation().is_none());
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        let mut total = 0;
+        for inversions in 0..=21 {
+            let mg = MahonianPermutationGenerator8::new(7, inversions).unwrap();
+            let members = assert_matches_filtered(&mg, |perm| nb_inversions(perm) == inversions);
+            assert!(members.is_sorted());
+            total += members.len();
+        }
+        assert_eq!(5040, total);
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[0, 1, 2, 3, 4, 5, 6, 7, 9, 8],
+            MahonianPermutationGenerator16::nth_absolute(10, 1, 0).unwrap(),
+        );
+        test_slice(
+            &[1, 0, 2, 3, 4, 5, 6, 7, 8, 9],
+            MahonianPermutationGenerator16::nth_absolute(10, 1, 8).unwrap(),
+        );
+        assert!(MahonianPermutationGenerator16::nth_absolute(10, 1, 9)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rank() {
+        let mg = MahonianPermutationGenerator32::new(32, 248).unwrap();
+        let idx = 123456789012345678901234567;
+        let perm = mg.permutation(idx).unwrap();
+        assert_eq!(248, nb_inversions(&perm));
+        assert_eq!(Ok(idx), mg.rank(&perm));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            MahonianPermutationGenerator8::new(4, 2)
+                .unwrap()
+                .rank(&[0, 2, 1, 3])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            MahonianPermutationGenerator8::new(4, 2)
+                .unwrap()
+                .rank(&[3, 2, 1, 0])
+        );
+    }
+}
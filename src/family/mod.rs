@@ -0,0 +1,211 @@
+use super::*;
+
+mod alternating_permutations;
+mod block_permutations;
+mod circular_permutations;
+mod cycle_count_permutations;
+mod cycle_type_permutations;
+mod derangements;
+mod eulerian_permutations;
+mod even_permutations;
+mod forbidden_positions;
+mod involutions;
+mod linear_extensions;
+mod mahonian_permutations;
+mod pattern_avoiding_permutations;
+mod rencontres_permutations;
+mod subset_counts;
+
+pub use alternating_permutations::*;
+pub use block_permutations::*;
+pub use circular_permutations::*;
+pub use cycle_count_permutations::*;
+pub use cycle_type_permutations::*;
+pub use derangements::*;
+pub use eulerian_permutations::*;
+pub use even_permutations::*;
+pub use forbidden_positions::*;
+pub use involutions::*;
+pub use linear_extensions::*;
+pub use mahonian_permutations::*;
+pub use pattern_avoiding_permutations::*;
+pub use rencontres_permutations::*;
+pub(crate) use subset_counts::*;
+
+/// The largest number of elements for the families counted with a table over all `2^n` subsets.
+pub const MAX_SUBSET_DP_ELEMENTS: u8 = 20;
+
+/// A combinatorial family of permutations of `0..nb_elems`, with direct rank and unrank.
+///
+/// Implementors only decode and encode a single member, `FamilyGenerator` provides the iteration.
+pub trait PermutationFamily<R: Rank>: Clone {
+    fn nb_elems(&self) -> u8;
+
+    /// The number of members, `TooManyElements` if it overflows `R`.
+    fn count(&self) -> PResult<R>;
+
+    /// Pushes the `idx`-th member into `buffer`, `idx` is below `count`.
+    fn unrank(&self, idx: R, buffer: &mut PermutationBuffer);
+
+    /// The index of `perm`, `NotInFamily` if it is a permutation of `0..nb_elems` outside the family.
+    fn rank(&self, perm: &[u8]) -> PResult<R>;
+}
+
+/// Generates the members of a `PermutationFamily`, with the same shape as `PermutationGenerator`.
+#[derive(Clone, Copy, Debug)]
+pub struct FamilyGenerator<F, R: Rank> {
+    family: F,
+    nb_perms: R,
+    next_idx: R,
+}
+
+impl<F: PermutationFamily<R>, R: Rank> FamilyGenerator<F, R> {
+    /// Like `PermutationGenerator`, a family over zero elements has no members.
+    pub fn from_family(family: F) -> PResult<Self> {
+        if family.nb_elems() > R::MAX_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        let nb_perms = if family.nb_elems() == 0 {
+            R::ZERO
+        } else {
+            family.count()?
+        };
+        Ok(Self {
+            family,
+            nb_perms,
+            next_idx: R::ZERO,
+        })
+    }
+
+    pub fn family(&self) -> &F {
+        &self.family
+    }
+
+    pub fn next_permutation(&mut self) -> Option<PermutationBuffer> {
+        self.nth(R::ZERO)
+    }
+
+    pub fn nth(&mut self, step: R) -> Option<PermutationBuffer> {
+        let step_result = self.next_idx.saturating_add(step);
+        self.next_idx = step_result.saturating_add(R::ONE);
+        self.permutation(step_result)
+    }
+
+    /// The `idx`-th member, regardless of the progress of the generator.
+    pub fn permutation(&self, idx: R) -> Option<PermutationBuffer> {
+        let mut buffer = PermutationBuffer::new();
+        self.permutation_into(idx, &mut buffer).then_some(buffer)
+    }
+
+    /// Like `permutation`, but writes into `buffer`, which is cleared first.
+    ///
+    /// Returns `false`, leaving `buffer` untouched, if `idx` is out of range.
+    pub fn permutation_into(&self, idx: R, buffer: &mut PermutationBuffer) -> bool {
+        if idx >= self.nb_perms {
+            return false;
+        }
+        buffer.clear();
+        self.family.unrank(idx, buffer);
+        true
+    }
+
+    /// The index of `perm`, `NotInFamily` for the empty permutation of a family over zero elements too.
+    pub fn rank(&self, perm: &[u8]) -> PResult<R> {
+        if perm.len() != self.family.nb_elems() as usize {
+            return Err(PermutationGeneratorError::LengthMismatch);
+        }
+        match self.family.rank(perm)? {
+            rank if rank < self.nb_perms => Ok(rank),
+            _ => Err(PermutationGeneratorError::NotInFamily),
+        }
+    }
+
+    /// The number of members, regardless of the progress of the generator.
+    pub fn nb_permutations(&self) -> R {
+        self.nb_perms
+    }
+
+    /// Panics if the number of remaining members overflows `usize`
+    pub fn nb_remaining(&self) -> usize {
+        if self.next_idx >= self.nb_perms {
+            return 0;
+        }
+        match (self.nb_perms - self.next_idx).to_usize() {
+            Some(nb) => nb,
+            None => panic!("The size of the iterator overflowed usize"),
+        }
+    }
+}
+
+impl<F: PermutationFamily<R>, R: Rank> Iterator for FamilyGenerator<F, R> {
+    type Item = PermutationBuffer;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_permutation()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let nb_remaining = self.nb_remaining();
+        (nb_remaining, Some(nb_remaining))
+    }
+
+    fn count(self) -> usize {
+        self.nb_remaining()
+    }
+}
+
+/// `nb_elems!`, with `0! = 1` as opposed to `Rank::factorial`, counting the ways to complete an empty arrangement.
+#[inline]
+pub(crate) fn nb_arrangements<R: Rank>(nb_elems: u8) -> R {
+    if nb_elems == 0 {
+        R::ONE
+    } else {
+        R::factorial(nb_elems)
+    }
+}
+
+/// Checks that `perm` is a permutation of `0..nb_elems`, calling `on_elem(elem, remaining)` on each element.
+///
+/// `remaining` is the bitmask of the elements not placed yet, `elem` included.
+#[inline]
+pub(crate) fn walk_permutation(
+    nb_elems: u8,
+    perm: &[u8],
+    mut on_elem: impl FnMut(u8, u64) -> PResult<()>,
+) -> PResult<()> {
+    let mut remaining = (1u64 << nb_elems) - 1;
+    for &elem in perm {
+        if elem >= nb_elems || remaining & (1 << elem) == 0 {
+            return Err(PermutationGeneratorError::NotAPermutation);
+        }
+        on_elem(elem, remaining)?;
+        remaining &= !(1 << elem);
+    }
+    Ok(())
+}
+
+/// Checks a family of up to 8 elements against the permutations filtered by `is_member`, and returns its members in
+/// the order they are generated.
+#[cfg(test)]
+pub(crate) fn assert_matches_filtered<F: PermutationFamily<u16>>(
+    fg: &FamilyGenerator<F, u16>,
+    is_member: impl Fn(&[u8]) -> bool,
+) -> Vec<Vec<u8>> {
+    let filtered = PermutationGenerator8::new(fg.family.nb_elems())
+        .unwrap()
+        .map(|perm| perm.collect::<Vec<_>>())
+        .filter(|perm| is_member(perm))
+        .collect::<Vec<_>>();
+    let generated = fg
+        .clone()
+        .map(|perm| perm.as_slice().to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(fg.nb_permutations() as usize, generated.len());
+    for (idx, perm) in generated.iter().enumerate() {
+        assert_eq!(Ok(idx as u16), fg.rank(perm));
+    }
+    let mut sorted = generated.clone();
+    sorted.sort_unstable();
+    assert_eq!(filtered, sorted);
+    generated
+}
@@ -0,0 +1,547 @@
+use super::*;
+
+/// Permutations avoiding a pattern of length 3, counted by the Catalan numbers, in lexicographic order.
+///
+/// `231` and `213` split around their first element, `132`, `312`, `123` and `321` are ranked by the values left
+/// below the running minimum. `213`, `312` and `321` are the complements of the others, which reverses the order.
+/// Longer patterns are enumerated by `PatternAvoidingEnumerator`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PatternAvoidingPermutations {
+    nb_elems: u8,
+    pattern: [u8; 3],
+    catalan_numbers: [u128; 33],
+    nb_completions_below_minimum: [[u128; 33]; 33],
+}
+
+pub type PatternAvoidingGenerator<R> = FamilyGenerator<PatternAvoidingPermutations, R>;
+pub type PatternAvoidingGenerator8 = PatternAvoidingGenerator<u16>;
+pub type PatternAvoidingGenerator16 = PatternAvoidingGenerator<u64>;
+pub type PatternAvoidingGenerator32 = PatternAvoidingGenerator<u128>;
+
+/// How a length 3 pattern, or its complement, is ranked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Decomposition {
+    /// `231`: the first element, then the smaller elements, then the larger ones.
+    Split,
+    /// `132`: past the running minimum, the remaining values increase.
+    MinimumThenIncreasing,
+    /// `123`: past the running minimum, the remaining values decrease.
+    MinimumThenDecreasing,
+}
+
+impl<R: Rank> FamilyGenerator<PatternAvoidingPermutations, R> {
+    /// `pattern` is a permutation of `0..3`, e.g. `[1, 2, 0]` for `231`.
+    pub fn new(nb_elems: u8, pattern: [u8; 3]) -> PResult<Self> {
+        check_permutation(&pattern)?;
+        PatternAvoidingPermutations::new(nb_elems, pattern).and_then(Self::from_family)
+    }
+
+    pub fn nth_absolute(
+        nb_elems: u8,
+        pattern: [u8; 3],
+        idx: R,
+    ) -> PResult<Option<PermutationBuffer>> {
+        Self::new(nb_elems, pattern).map(|pg| pg.permutation(idx))
+    }
+}
+
+impl PatternAvoidingPermutations {
+    fn new(nb_elems: u8, pattern: [u8; 3]) -> PResult<Self> {
+        if nb_elems > u128::MAX_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        Ok(Self {
+            nb_elems,
+            pattern,
+            catalan_numbers: catalan_numbers(nb_elems),
+            nb_completions_below_minimum: nb_completions_below_minimum(nb_elems),
+        })
+    }
+
+    #[inline]
+    fn catalan<R: Rank>(&self, nb_elems: usize) -> R {
+        R::from_u128(self.catalan_numbers[nb_elems]).unwrap()
+    }
+
+    #[inline]
+    fn nb_completions<R: Rank>(&self, nb_remaining: usize, nb_below: usize) -> R {
+        R::from_u128(self.nb_completions_below_minimum[nb_remaining][nb_below]).unwrap()
+    }
+
+    fn decomposition(&self) -> (Decomposition, bool) {
+        match self.pattern {
+            [1, 2, 0] => (Decomposition::Split, false),
+            [1, 0, 2] => (Decomposition::Split, true),
+            [0, 2, 1] => (Decomposition::MinimumThenIncreasing, false),
+            [2, 0, 1] => (Decomposition::MinimumThenIncreasing, true),
+            [0, 1, 2] => (Decomposition::MinimumThenDecreasing, false),
+            _ => (Decomposition::MinimumThenDecreasing, true),
+        }
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for PatternAvoidingPermutations {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        R::from_u128(self.catalan_numbers[self.nb_elems as usize])
+            .ok_or(PermutationGeneratorError::TooManyElements)
+    }
+
+    fn unrank(&self, idx: R, buffer: &mut PermutationBuffer) {
+        let (decomposition, is_complement) = self.decomposition();
+        let nb_elems = self.nb_elems;
+        let idx = if is_complement {
+            self.catalan::<R>(nb_elems as usize) - R::ONE - idx
+        } else {
+            idx
+        };
+        match decomposition {
+            Decomposition::Split => self.unrank_split(0, nb_elems, idx, buffer),
+            _ => self.unrank_below_minimum(
+                decomposition == Decomposition::MinimumThenIncreasing,
+                idx,
+                buffer,
+            ),
+        }
+        if is_complement {
+            buffer
+                .as_mut_slice()
+                .iter_mut()
+                .for_each(|elem| *elem = nb_elems - 1 - *elem);
+        }
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        check_permutation(perm)?;
+        let (decomposition, is_complement) = self.decomposition();
+        let nb_elems = self.nb_elems;
+        let perm = perm
+            .iter()
+            .map(|&elem| {
+                if is_complement {
+                    nb_elems - 1 - elem
+                } else {
+                    elem
+                }
+            })
+            .collect::<PermutationBuffer>();
+        let rank = match decomposition {
+            Decomposition::Split => self.rank_split(&perm, 0),
+            _ => self
+                .rank_below_minimum(decomposition == Decomposition::MinimumThenIncreasing, &perm),
+        }?;
+        Ok(if is_complement {
+            self.catalan::<R>(nb_elems as usize) - R::ONE - rank
+        } else {
+            rank
+        })
+    }
+}
+
+impl PatternAvoidingPermutations {
+    /// Pushes the `idx`-th `231`-avoiding permutation of `offset..offset + nb_elems`.
+    fn unrank_split<R: Rank>(
+        &self,
+        offset: u8,
+        nb_elems: u8,
+        mut idx: R,
+        buffer: &mut PermutationBuffer,
+    ) {
+        if nb_elems == 0 {
+            return;
+        }
+        let mut first = 0;
+        let nb_split = |first: u8| {
+            self.catalan::<R>(first as usize) * self.catalan((nb_elems - 1 - first) as usize)
+        };
+        while idx >= nb_split(first) {
+            idx -= nb_split(first);
+            first += 1;
+        }
+        let nb_larger_perms = self.catalan::<R>((nb_elems - 1 - first) as usize);
+        buffer.push(offset + first);
+        self.unrank_split(offset, first, idx / nb_larger_perms, buffer);
+        self.unrank_split(
+            offset + first + 1,
+            nb_elems - 1 - first,
+            idx % nb_larger_perms,
+            buffer,
+        );
+    }
+
+    /// The rank of a permutation of `offset..offset + perm.len()` among the `231`-avoiding ones.
+    fn rank_split<R: Rank>(&self, perm: &[u8], offset: u8) -> PResult<R> {
+        let Some(&first_elem) = perm.first() else {
+            return Ok(R::ZERO);
+        };
+        let nb_elems = perm.len();
+        let first = (first_elem - offset) as usize;
+        let (smaller, larger) = perm[1..].split_at(first);
+        if smaller.iter().any(|&elem| elem > first_elem) {
+            return Err(PermutationGeneratorError::NotInFamily);
+        }
+        let before = (0..first).fold(R::ZERO, |before, smaller_first| {
+            before + self.catalan::<R>(smaller_first) * self.catalan(nb_elems - 1 - smaller_first)
+        });
+        Ok(before
+            + self.rank_split::<R>(smaller, offset)? * self.catalan(nb_elems - 1 - first)
+            + self.rank_split::<R>(larger, first_elem + 1)?)
+    }
+
+    /// Pushes the `idx`-th permutation avoiding `132`, or `123` if not `is_increasing`.
+    ///
+    /// Any remaining value below the running minimum may come next, otherwise only the smallest remaining value above it
+    /// for `132`, or the largest one for `123`.
+    fn unrank_below_minimum<R: Rank>(
+        &self,
+        is_increasing: bool,
+        mut idx: R,
+        buffer: &mut PermutationBuffer,
+    ) {
+        let nb_elems = self.nb_elems;
+        let mut remaining = (1u64 << nb_elems) - 1;
+        let mut minimum = nb_elems;
+        for nb_remaining in (1..=nb_elems as usize).rev() {
+            let below = remaining & ((1 << minimum) - 1);
+            let mut elem = None;
+            for (nb_below, below_elem) in (0..below.count_ones() as usize).zip(bits(below)) {
+                let nb = self.nb_completions::<R>(nb_remaining - 1, nb_below);
+                if idx < nb {
+                    elem = Some(below_elem);
+                    minimum = below_elem;
+                    break;
+                }
+                idx -= nb;
+            }
+            let elem =
+                elem.unwrap_or_else(|| next_above_minimum(remaining, minimum, is_increasing));
+            buffer.push(elem);
+            remaining &= !(1 << elem);
+        }
+    }
+
+    fn rank_below_minimum<R: Rank>(&self, is_increasing: bool, perm: &[u8]) -> PResult<R> {
+        let nb_elems = self.nb_elems;
+        let mut minimum = nb_elems;
+        let mut rank = R::ZERO;
+        walk_permutation(nb_elems, perm, |elem, remaining| {
+            let nb_remaining = remaining.count_ones() as usize;
+            let below = remaining & ((1 << minimum) - 1);
+            let nb_smaller = if elem < minimum {
+                (below & ((1 << elem) - 1)).count_ones() as usize
+            } else if elem == next_above_minimum(remaining, minimum, is_increasing) {
+                below.count_ones() as usize
+            } else {
+                return Err(PermutationGeneratorError::NotInFamily);
+            };
+            for nb_below in 0..nb_smaller {
+                rank += self.nb_completions::<R>(nb_remaining - 1, nb_below);
+            }
+            minimum = minimum.min(elem);
+            Ok(())
+        })
+        .map(|_| rank)
+    }
+}
+
+#[inline]
+fn next_above_minimum(remaining: u64, minimum: u8, is_increasing: bool) -> u8 {
+    let above = remaining >> minimum << minimum;
+    if is_increasing {
+        above.trailing_zeros() as u8
+    } else {
+        63 - above.leading_zeros() as u8
+    }
+}
+
+#[inline]
+fn bits(mask: u64) -> impl Iterator<Item = u8> {
+    (0..64).filter(move |&bit| mask & (1 << bit) != 0)
+}
+
+/// `table[r][t]`, the number of ways to order `r` remaining values, `t` of them below the running minimum.
+///
+/// Either the `j`-th of the `t` values comes next and leaves `j` values below the new minimum, or the single allowed
+/// value above it does and leaves `t`, so `table[r][t]` sums `table[r - 1][0..=t]`.
+fn nb_completions_below_minimum(nb_elems: u8) -> [[u128; 33]; 33] {
+    let mut table = [[0; 33]; 33];
+    table[0][0] = 1;
+    for nb_remaining in 1..=nb_elems as usize {
+        let previous = table[nb_remaining - 1];
+        let mut nb = 0;
+        for (entry, &previous_entry) in table[nb_remaining]
+            .iter_mut()
+            .zip(&previous)
+            .take(nb_remaining + 1)
+        {
+            nb += previous_entry;
+            *entry = nb;
+        }
+    }
+    table
+}
+
+/// The Catalan numbers `C(0..=nb_elems)`.
+fn catalan_numbers(nb_elems: u8) -> [u128; 33] {
+    let mut catalan = [0; 33];
+    for n in 0..=nb_elems {
+        catalan[n as usize] = numbers::catalan128(n).unwrap();
+    }
+    catalan
+}
+
+/// Enumerates the permutations avoiding a pattern of any length in lexicographic order, pruning the prefixes
+/// which already contain it.
+#[derive(Clone, Debug)]
+pub struct PatternAvoidingEnumerator {
+    nb_elems: u8,
+    pattern: PermutationBuffer,
+    prefix: PermutationBuffer,
+    used: u32,
+    next_candidates: [u8; 32],
+    is_done: bool,
+}
+
+impl PatternAvoidingEnumerator {
+    /// `pattern` is a permutation of `0..pattern.len()`.
+    pub fn new(nb_elems: u8, pattern: &[u8]) -> PResult<Self> {
+        if nb_elems > 32 || pattern.len() > 32 {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        check_permutation(pattern)?;
+        Ok(Self {
+            nb_elems,
+            pattern: pattern.iter().copied().collect(),
+            prefix: PermutationBuffer::new(),
+            used: 0,
+            next_candidates: [0; 32],
+            is_done: nb_elems == 0,
+        })
+    }
+
+    /// Whether appending `elem` to the prefix completes an occurrence of the pattern.
+    fn completes_pattern(&self, elem: u8) -> bool {
+        let Some((&last, pattern)) = self.pattern.split_last() else {
+            return true;
+        };
+        if pattern.len() > self.prefix.len() {
+            return false;
+        }
+        let mut chosen = [(last, elem); 32];
+        self.matches_from(pattern, 0, &mut chosen, 1)
+    }
+
+    /// Chooses increasing prefix positions from `start` for `pattern`, in the same relative order as the
+    /// `(pattern_elem, elem)` pairs `chosen` so far.
+    fn matches_from(
+        &self,
+        pattern: &[u8],
+        start: usize,
+        chosen: &mut [(u8, u8); 32],
+        nb_chosen: usize,
+    ) -> bool {
+        let Some((&pattern_elem, rest)) = pattern.split_first() else {
+            return true;
+        };
+        for pos in start..=self.prefix.len() - pattern.len() {
+            let elem = self.prefix[pos];
+            if chosen[..nb_chosen]
+                .iter()
+                .all(|&(other_pattern_elem, other)| {
+                    (pattern_elem < other_pattern_elem) == (elem < other)
+                })
+            {
+                chosen[nb_chosen] = (pattern_elem, elem);
+                if self.matches_from(rest, pos + 1, chosen, nb_chosen + 1) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+impl Iterator for PatternAvoidingEnumerator {
+    type Item = PermutationBuffer;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.is_done {
+            let depth = self.prefix.len();
+            let candidate = (self.next_candidates[depth]..self.nb_elems)
+                .find(|&elem| self.used & (1 << elem) == 0 && !self.completes_pattern(elem));
+            match candidate {
+                Some(elem) if depth + 1 == self.nb_elems as usize => {
+                    self.next_candidates[depth] = elem + 1;
+                    let mut perm = self.prefix;
+                    perm.push(elem);
+                    return Some(perm);
+                }
+                Some(elem) => {
+                    self.next_candidates[depth] = elem + 1;
+                    self.prefix.push(elem);
+                    self.used |= 1 << elem;
+                    self.next_candidates[depth + 1] = 0;
+                }
+                None if depth == 0 => self.is_done = true,
+                None => {
+                    let elem = self.prefix.pop().unwrap();
+                    self.used &= !(1 << elem);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATTERNS: [[u8; 3]; 6] = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+
+    fn contains(perm: &[u8], pattern: &[u8]) -> bool {
+        fn contains_from(perm: &[u8], pattern: &[u8], chosen: &mut Vec<u8>, start: usize) -> bool {
+            if chosen.len() == pattern.len() {
+                return (0..chosen.len()).all(|a| {
+                    (0..chosen.len()).all(|b| (pattern[a] < pattern[b]) == (chosen[a] < chosen[b]))
+                });
+            }
+            (start..perm.len()).any(|pos| {
+                chosen.push(perm[pos]);
+                let res = contains_from(perm, pattern, chosen, pos + 1);
+                chosen.pop();
+                res
+            })
+        }
+        contains_from(perm, pattern, &mut Vec::new(), 0)
+    }
+
+    fn avoiding(nb_elems: u8, pattern: &[u8]) -> Vec<Vec<u8>> {
+        PermutationGenerator8::new(nb_elems)
+            .unwrap()
+            .map(|perm| perm.collect::<Vec<_>>())
+            .filter(|perm| !contains(perm, pattern))
+            .collect()
+    }
+
+    #[test]
+    fn new() {
+        for pattern in PATTERNS {
+            assert_eq!(
+                1430,
+                PatternAvoidingGenerator8::new(8, pattern)
+                    .unwrap()
+                    .nb_permutations()
+            );
+            assert_eq!(
+                55534064877048198,
+                PatternAvoidingGenerator32::new(32, pattern)
+                    .unwrap()
+                    .nb_permutations()
+            );
+        }
+        assert_eq!(
+            PermutationGeneratorError::NotAPermutation,
+            PatternAvoidingGenerator8::new(4, [0, 0, 1]).unwrap_err()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            PatternAvoidingGenerator32::new(33, [1, 2, 0]).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        for pattern in PATTERNS {
+            let pg = PatternAvoidingGenerator8::new(6, pattern).unwrap();
+            let members = assert_matches_filtered(&pg, |perm| !contains(perm, &pattern));
+            assert_eq!(132, members.len());
+            assert!(members.is_sorted());
+            assert_eq!(
+                members,
+                PatternAvoidingEnumerator::new(6, &pattern)
+                    .unwrap()
+                    .map(|perm| perm.collect::<Vec<_>>())
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn nth_absolute() {
+        assert_eq!(
+            &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+            PatternAvoidingGenerator16::nth_absolute(16, [1, 2, 0], 0)
+                .unwrap()
+                .unwrap()
+                .as_slice()
+        );
+        assert_eq!(
+            &[15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+            PatternAvoidingGenerator16::nth_absolute(16, [0, 2, 1], 35357669)
+                .unwrap()
+                .unwrap()
+                .as_slice()
+        );
+        assert!(
+            PatternAvoidingGenerator16::nth_absolute(16, [2, 1, 0], 35357670)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn rank() {
+        for pattern in PATTERNS {
+            let pg = PatternAvoidingGenerator32::new(32, pattern).unwrap();
+            let idx = 12345678901234567;
+            let perm = pg.permutation(idx).unwrap();
+            assert!(!contains(&perm[..12], &pattern));
+            assert_eq!(Ok(idx), pg.rank(&perm));
+            assert_eq!(
+                Err(PermutationGeneratorError::NotInFamily),
+                PatternAvoidingGenerator8::new(4, pattern).unwrap().rank(&[
+                    pattern[0] + 1,
+                    0,
+                    pattern[1] + 1,
+                    pattern[2] + 1
+                ])
+            );
+        }
+    }
+
+    #[test]
+    fn longer_patterns() {
+        for pattern in [[0, 1, 2, 3], [0, 3, 2, 1], [0, 2, 3, 1], [1, 3, 0, 2]] {
+            let filtered = avoiding(7, &pattern);
+            let enumerated = PatternAvoidingEnumerator::new(7, &pattern)
+                .unwrap()
+                .map(|perm| perm.collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            assert_eq!(filtered, enumerated);
+        }
+        assert_eq!(
+            513,
+            PatternAvoidingEnumerator::new(6, &[0, 1, 2, 3])
+                .unwrap()
+                .count()
+        );
+        assert_eq!(
+            512,
+            PatternAvoidingEnumerator::new(6, &[0, 2, 3, 1])
+                .unwrap()
+                .count()
+        );
+        assert_eq!(0, PatternAvoidingEnumerator::new(3, &[0]).unwrap().count());
+    }
+}
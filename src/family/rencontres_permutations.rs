@@ -0,0 +1,210 @@
+use super::*;
+
+/// Permutations with exactly `nb_fixed_points` fixed points, `p[i] == i`, counted by the rencontres numbers.
+///
+/// `Derangements` are the class without fixed points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RencontresPermutations {
+    nb_elems: u8,
+    nb_fixed_points: u8,
+}
+
+pub type RencontresPermutationGenerator<R> = FamilyGenerator<RencontresPermutations, R>;
+pub type RencontresPermutationGenerator8 = RencontresPermutationGenerator<u16>;
+pub type RencontresPermutationGenerator16 = RencontresPermutationGenerator<u64>;
+pub type RencontresPermutationGenerator32 = RencontresPermutationGenerator<u128>;
+
+impl<R: Rank> FamilyGenerator<RencontresPermutations, R> {
+    pub fn new(nb_elems: u8, nb_fixed_points: u8) -> PResult<Self> {
+        Self::from_family(RencontresPermutations {
+            nb_elems,
+            nb_fixed_points,
+        })
+    }
+
+    pub fn nth_absolute(
+        nb_elems: u8,
+        nb_fixed_points: u8,
+        idx: R,
+    ) -> PResult<Option<PermutationBuffer>> {
+        Self::new(nb_elems, nb_fixed_points).map(|rg| rg.permutation(idx))
+    }
+}
+
+impl RencontresPermutations {
+    /// The number of completions of a prefix upto `pos`, leaving the elements in `remaining`, with `fixed_left`
+    /// fixed points still to come, if any.
+    ///
+    /// These are picked among the remaining elements whose position is still free, the others are deranged.
+    #[inline]
+    fn nb_completions<R: Rank>(&self, pos: u8, remaining: u64, fixed_left: Option<u8>) -> R {
+        let nb_constrained = (remaining >> (pos + 1)).count_ones() as u8;
+        match fixed_left {
+            Some(fixed_left) if fixed_left <= nb_constrained => {
+                nb_combinations::<R>(nb_constrained, fixed_left)
+                    * nb_partial_derangements(
+                        self.nb_elems - pos - 1 - fixed_left,
+                        nb_constrained - fixed_left,
+                    )
+            }
+            _ => R::ZERO,
+        }
+    }
+}
+
+impl<R: Rank> PermutationFamily<R> for RencontresPermutations {
+    fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    fn count(&self) -> PResult<R> {
+        Ok(if self.nb_fixed_points > self.nb_elems {
+            R::ZERO
+        } else {
+            let nb_deranged = self.nb_elems - self.nb_fixed_points;
+            nb_combinations::<R>(self.nb_elems, self.nb_fixed_points)
+                * nb_partial_derangements(nb_deranged, nb_deranged)
+        })
+    }
+
+    fn unrank(&self, mut idx: R, buffer: &mut PermutationBuffer) {
+        let mut remaining = (1u64 << self.nb_elems) - 1;
+        let mut fixed_left = self.nb_fixed_points;
+        for pos in 0..self.nb_elems {
+            for elem in (0..self.nb_elems).filter(|&elem| remaining & (1 << elem) != 0) {
+                let left = fixed_left.checked_sub((elem == pos) as u8);
+                let nb = self.nb_completions::<R>(pos, remaining & !(1 << elem), left);
+                if idx < nb {
+                    buffer.push(elem);
+                    remaining &= !(1 << elem);
+                    fixed_left -= (elem == pos) as u8;
+                    break;
+                }
+                idx -= nb;
+            }
+        }
+    }
+
+    fn rank(&self, perm: &[u8]) -> PResult<R> {
+        let mut rank = R::ZERO;
+        let mut pos = 0;
+        let mut fixed_left = Some(self.nb_fixed_points);
+        walk_permutation(self.nb_elems, perm, |elem, remaining| {
+            let left =
+                |elem: u8| fixed_left.and_then(|fixed| fixed.checked_sub((elem == pos) as u8));
+            for smaller in (0..elem).filter(|&smaller| remaining & (1 << smaller) != 0) {
+                rank += self.nb_completions::<R>(pos, remaining & !(1 << smaller), left(smaller));
+            }
+            fixed_left = left(elem);
+            pos += 1;
+            Ok(())
+        })?;
+        if fixed_left == Some(0) {
+            Ok(rank)
+        } else {
+            Err(PermutationGeneratorError::NotInFamily)
+        }
+    }
+}
+
+/// The binomial coefficient `nb_elems` choose `nb_picked`, below `nb_elems!` so it fits any `R` for `nb_elems`.
+#[inline]
+fn nb_combinations<R: Rank>(nb_elems: u8, nb_picked: u8) -> R {
+    R::from_u128(numbers::binomial128(nb_elems, nb_picked).unwrap()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_buffer: Option<PermutationBuffer>) {
+        assert_eq!(ref_slice, some_buffer.unwrap().as_slice());
+    }
+
+    fn nb_fixed_points(perm: &[u8]) -> u8 {
+        perm.iter()
+            .enumerate()
+            .filter(|&(pos, &elem)| pos == elem as usize)
+            .count() as u8
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            14832,
+            RencontresPermutationGenerator8::new(8, 1)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            1,
+            RencontresPermutationGenerator16::new(16, 16)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            0,
+            RencontresPermutationGenerator16::new(16, 15)
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            DerangementGenerator32::new(32).unwrap().nb_permutations(),
+            RencontresPermutationGenerator32::new(32, 0)
+                .unwrap()
+                .nb_permutations()
+        );
+    }
+
+    #[test]
+    fn next_permutation() {
+        let mut rg = RencontresPermutationGenerator8::new(3, 1).unwrap();
+        test_slice(&[0, 2, 1], rg.next_permutation());
+        test_slice(&[1, 0, 2], rg.next_permutation());
+        test_slice(&[2, 1, 0], rg.next_permutation());
+        assert!(rg.next_permutation().is_none());
+    }
+
+    #[test]
+    fn matches_filtered_permutations() {
+        let mut total = 0;
+        for fixed_points in 0..=8 {
+            let rg = RencontresPermutationGenerator8::new(7, fixed_points).unwrap();
+            let members =
+                assert_matches_filtered(&rg, |perm| nb_fixed_points(perm) == fixed_points);
+            assert!(members.is_sorted());
+            total += members.len();
+        }
+        assert_eq!(5040, total);
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[0, 1, 2, 3, 4, 5, 6, 7, 9, 8],
+            RencontresPermutationGenerator16::nth_absolute(10, 8, 0).unwrap(),
+        );
+        test_slice(
+            &[9, 1, 2, 3, 4, 5, 6, 7, 8, 0],
+            RencontresPermutationGenerator16::nth_absolute(10, 8, 44).unwrap(),
+        );
+        assert!(RencontresPermutationGenerator16::nth_absolute(10, 8, 45)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rank() {
+        let rg = RencontresPermutationGenerator32::new(32, 5).unwrap();
+        let idx = 1234567890123456789012345678901;
+        let perm = rg.permutation(idx).unwrap();
+        assert_eq!(5, nb_fixed_points(&perm));
+        assert_eq!(Ok(idx), rg.rank(&perm));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotInFamily),
+            RencontresPermutationGenerator8::new(4, 1)
+                .unwrap()
+                .rank(&[0, 1, 3, 2])
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use super::*;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// The number of ways to complete each prefix of a family, indexed by the set of elements already placed.
+///
+/// `is_allowed(placed, elem)` tells whether `elem`, not in `placed`, may come right after the elements of `placed`.
+/// Members are ordered lexicographically, and every count used to rank a member fits in any `R` counting them all.
+///
+/// The table is shared between clones, and left out of comparisons and hashing: it only depends on `is_allowed`, which
+/// the owning family compares on its own.
+#[derive(Clone)]
+pub(crate) struct SubsetCounts {
+    nb_elems: u8,
+    nb_completions: Arc<[u128]>,
+}
+
+impl SubsetCounts {
+    pub(crate) fn new(nb_elems: u8, is_allowed: impl Fn(BitIndex32, u8) -> bool) -> PResult<Self> {
+        if nb_elems > MAX_SUBSET_DP_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        let all = BitIndex32::new(nb_elems).unwrap();
+        let mut nb_completions = vec![0; all.unwrap() as usize + 1];
+        nb_completions[all.unwrap() as usize] = 1;
+        for bits in (0..all.unwrap()).rev() {
+            let mut placed = BitIndex32::empty(nb_elems).unwrap();
+            placed.add(bits);
+            nb_completions[bits as usize] = Self::candidates(placed, &is_allowed)
+                .map(|elem| nb_completions[Self::with(placed, elem)])
+                .sum();
+        }
+        Ok(Self {
+            nb_elems,
+            nb_completions: nb_completions.into(),
+        })
+    }
+
+    pub(crate) fn count<R: Rank>(&self) -> PResult<R> {
+        R::from_u128(self.nb_completions[0]).ok_or(PermutationGeneratorError::TooManyElements)
+    }
+
+    pub(crate) fn unrank<R: Rank>(
+        &self,
+        mut idx: R,
+        buffer: &mut PermutationBuffer,
+        is_allowed: impl Fn(BitIndex32, u8) -> bool,
+    ) {
+        let mut placed = BitIndex32::empty(self.nb_elems).unwrap();
+        for _ in 0..self.nb_elems {
+            for elem in Self::candidates(placed, &is_allowed) {
+                let nb = self.nb_completions::<R>(placed, elem);
+                if idx < nb {
+                    buffer.push(elem);
+                    placed.set_bit(elem);
+                    break;
+                }
+                idx -= nb;
+            }
+        }
+    }
+
+    pub(crate) fn rank<R: Rank>(
+        &self,
+        perm: &[u8],
+        is_allowed: impl Fn(BitIndex32, u8) -> bool,
+    ) -> PResult<R> {
+        let mut placed = BitIndex32::empty(self.nb_elems).unwrap();
+        let mut rank = R::ZERO;
+        walk_permutation(self.nb_elems, perm, |elem, _| {
+            if !is_allowed(placed, elem) {
+                return Err(PermutationGeneratorError::NotInFamily);
+            }
+            for smaller in
+                Self::candidates(placed, &is_allowed).take_while(|&smaller| smaller < elem)
+            {
+                rank += self.nb_completions::<R>(placed, smaller);
+            }
+            placed.set_bit(elem);
+            Ok(())
+        })
+        .map(|_| rank)
+    }
+
+    /// The elements not in `placed` that may come next, in increasing order.
+    #[inline]
+    fn candidates(
+        placed: BitIndex32,
+        is_allowed: &impl Fn(BitIndex32, u8) -> bool,
+    ) -> impl Iterator<Item = u8> + '_ {
+        let mut free = placed;
+        free.restore();
+        let mut placed_elems = placed;
+        while let Some(elem) = placed_elems.pop_smallest() {
+            free.unset_bit(elem);
+        }
+        std::iter::from_fn(move || free.pop_smallest())
+            .filter(move |&elem| is_allowed(placed, elem))
+    }
+
+    /// The index in the table of `placed`, with `elem` placed as well.
+    #[inline]
+    fn with(mut placed: BitIndex32, elem: u8) -> usize {
+        placed.set_bit(elem);
+        placed.unwrap() as usize
+    }
+
+    #[inline]
+    fn nb_completions<R: Rank>(&self, placed: BitIndex32, elem: u8) -> R {
+        R::from_u128(self.nb_completions[Self::with(placed, elem)]).unwrap()
+    }
+}
+
+impl fmt::Debug for SubsetCounts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubsetCounts")
+            .field("nb_elems", &self.nb_elems)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for SubsetCounts {
+    fn eq(&self, other: &Self) -> bool {
+        self.nb_elems == other.nb_elems
+    }
+}
+
+impl Eq for SubsetCounts {}
+
+impl Hash for SubsetCounts {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.nb_elems.hash(state);
+    }
+}
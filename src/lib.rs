@@ -1,18 +1,41 @@
-#![feature(type_alias_impl_trait)]
-
-use std::convert::TryInto;
+#![feature(impl_trait_in_assoc_type)]
 
 use bit_index::*;
 
+mod anagram_generator;
+mod direct_permutations;
 mod error;
+mod factoradic;
 mod factorial;
+mod family;
+pub mod numbers;
+mod permutation_buffer;
 mod permutation_generator;
+mod permutation_statistics;
+mod permutation_table;
+mod product_permutation_generator;
+mod rank;
+mod signed_permutation_generator;
 mod single_permutation;
+mod subset_permutation_generator;
+mod word_generator;
 
+pub use anagram_generator::*;
+pub use direct_permutations::*;
 pub use error::*;
+pub use factoradic::*;
 pub(crate) use factorial::*;
+pub use family::*;
+pub use permutation_buffer::*;
 pub use permutation_generator::*;
+pub use permutation_statistics::*;
+pub use permutation_table::*;
+pub use product_permutation_generator::*;
+pub use rank::*;
+pub use signed_permutation_generator::*;
 pub(crate) use single_permutation::*;
+pub use subset_permutation_generator::*;
+pub use word_generator::*;
 
 // #[derive(Clone)]
 // pub struct PermutationGeneratorWithReferences8<'a, T: 'a + Clone> {
@@ -0,0 +1,465 @@
+//! Combinatorial numbers as `const fn`s, `None` when the result overflows the returned width.
+//!
+//! Every sequence is tabulated at compile time in `u128` statics, up to its last value that fits, and narrowed with
+//! a check for `u16` and `u64`. Unlike the permutation counts of the generators, `0! == 1` here.
+
+/// `n!` for `n <= 34`.
+static FACTORIALS: [u128; 35] = {
+    let mut table = [1; 35];
+    let mut n = 1;
+    while n < table.len() {
+        table[n] = table[n - 1] * n as u128;
+        n += 1;
+    }
+    table
+};
+
+/// `!n = (n - 1)(!(n - 1) + !(n - 2))`, for `n <= 34`.
+static SUBFACTORIALS: [u128; 35] = {
+    let mut table = [1; 35];
+    table[1] = 0;
+    let mut n = 2;
+    while n < table.len() {
+        table[n] = (table[n - 1] + table[n - 2]) * (n as u128 - 1);
+        n += 1;
+    }
+    table
+};
+
+/// `C(n + 1) = sum C(i) C(n - i)`, for `n <= 69`.
+static CATALAN_NUMBERS: [u128; 70] = {
+    let mut table = [1; 70];
+    let mut n = 1;
+    while n < table.len() {
+        let mut sum = 0;
+        let mut i = 0;
+        while i < n {
+            sum += table[i] * table[n - 1 - i];
+            i += 1;
+        }
+        table[n] = sum;
+        n += 1;
+    }
+    table
+};
+
+/// The first column of the Bell triangle, each row starting with the last value of the previous one, for `n <= 42`.
+static BELL_NUMBERS: [u128; 43] = {
+    let mut table = [1; 43];
+    let mut row = [0; 43];
+    row[0] = 1;
+    let mut n = 1;
+    while n < table.len() {
+        table[n] = row[n - 1];
+        if n + 1 < table.len() {
+            let mut next = [0; 43];
+            next[0] = row[n - 1];
+            let mut i = 1;
+            while i <= n {
+                next[i] = next[i - 1] + row[i - 1];
+                i += 1;
+            }
+            row = next;
+        }
+        n += 1;
+    }
+    table
+};
+
+/// The Euler zigzag numbers, the last values of the rows of the Seidel-Entringer triangle, for `n <= 38`.
+static EULER_NUMBERS: [u128; 39] = {
+    let mut table = [1; 39];
+    let mut row = [0; 39];
+    row[0] = 1;
+    let mut n = 1;
+    while n < table.len() {
+        let mut next = [0; 39];
+        let mut k = 1;
+        while k <= n {
+            next[k] = next[k - 1] + row[n - k];
+            k += 1;
+        }
+        table[n] = next[n];
+        row = next;
+        n += 1;
+    }
+    table
+};
+
+/// Past this many blocks and this many elements beyond the blocks, Stirling numbers overflow `u128`.
+///
+/// `S(n, k) >= k^(n - k)`, placing the first `k` elements apart and the others anywhere, and `c(n, k) >= S(n, k)`.
+const STIRLING_ROW_LEN: usize = 27;
+
+#[inline]
+const fn mul(a: Option<u128>, b: u128) -> Option<u128> {
+    match a {
+        Some(a) => a.checked_mul(b),
+        None => None,
+    }
+}
+
+#[inline]
+const fn lookup(table: &[u128], n: u8) -> Option<u128> {
+    if (n as usize) < table.len() {
+        Some(table[n as usize])
+    } else {
+        None
+    }
+}
+
+const fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+const fn falling_factorial(n: u8, k: u8) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+    if let (Some(n_fact), Some(rest_fact)) = (lookup(&FACTORIALS, n), lookup(&FACTORIALS, n - k)) {
+        return Some(n_fact / rest_fact);
+    }
+    let mut nb = Some(1);
+    let mut j = 0;
+    while j < k {
+        nb = mul(nb, (n - j) as u128);
+        j += 1;
+    }
+    nb
+}
+
+/// Multiplies by `(n - j) / (j + 1)` at each step, dividing first so that only the result can overflow.
+const fn binomial(n: u32, k: u32) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+    if n < FACTORIALS.len() as u32 {
+        return Some(
+            FACTORIALS[n as usize] / FACTORIALS[k as usize] / FACTORIALS[(n - k) as usize],
+        );
+    }
+    let k = if k < n - k { k } else { n - k };
+    let mut nb: u128 = 1;
+    let mut j = 0;
+    while j < k {
+        let divisor = gcd(nb, j as u128 + 1);
+        match (nb / divisor).checked_mul((n - j) as u128 / ((j as u128 + 1) / divisor)) {
+            Some(next) => nb = next,
+            None => return None,
+        }
+        j += 1;
+    }
+    Some(nb)
+}
+
+const fn multinomial(parts: &[u8]) -> Option<u128> {
+    let mut nb = Some(1);
+    let mut total = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        total += parts[i] as u32;
+        nb = match binomial(total, parts[i] as u32) {
+            Some(binomial) => mul(nb, binomial),
+            None => None,
+        };
+        i += 1;
+    }
+    nb
+}
+
+/// `c(n, k) = c(n - 1, k - 1) + (n - 1) c(n - 1, k)` and `S(n, k) = S(n - 1, k - 1) + k S(n - 1, k)`.
+///
+/// Only the values with at most `k` blocks and `n - k` elements beyond them are computed, every one of them bounding
+/// the result, along the rows of the triangle when `k` is the smaller, along its diagonals otherwise.
+const fn stirling(is_first_kind: bool, n: u8, k: u8) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+    let (n, k) = (n as usize, k as usize);
+    let nb_beyond = n - k;
+    if k >= STIRLING_ROW_LEN && nb_beyond >= STIRLING_ROW_LEN {
+        return None;
+    }
+    let mut row = [0u128; STIRLING_ROW_LEN];
+    row[0] = 1;
+    if k <= nb_beyond {
+        // row[j] == s(m, j)
+        let mut m = 1;
+        while m <= n {
+            let mut j = if m < k { m } else { k };
+            let first = if m > nb_beyond { m - nb_beyond } else { 1 };
+            while j >= first {
+                let factor = if is_first_kind { m - 1 } else { j };
+                row[j] = match row[j].checked_mul(factor as u128) {
+                    Some(nb) => match nb.checked_add(row[j - 1]) {
+                        Some(nb) => nb,
+                        None => return None,
+                    },
+                    None => return None,
+                };
+                j -= 1;
+            }
+            row[0] = 0;
+            m += 1;
+        }
+        Some(row[k])
+    } else {
+        // row[d] == s(j + d, j)
+        let mut j = 1;
+        while j <= k {
+            let mut d = 1;
+            while d <= nb_beyond {
+                let factor = if is_first_kind { j + d - 1 } else { j };
+                row[d] = match row[d - 1].checked_mul(factor as u128) {
+                    Some(nb) => match nb.checked_add(row[d]) {
+                        Some(nb) => nb,
+                        None => return None,
+                    },
+                    None => return None,
+                };
+                d += 1;
+            }
+            j += 1;
+        }
+        Some(row[nb_beyond])
+    }
+}
+
+macro_rules! impl_numbers {
+    ($uint:ty, $narrow:ident, $factorial:ident, $falling_factorial:ident, $binomial:ident, $multinomial:ident,
+     $subfactorial:ident, $stirling_first:ident, $stirling_second:ident, $bell:ident, $catalan:ident,
+     $euler:ident) => {
+        #[inline]
+        const fn $narrow(value: Option<u128>) -> Option<$uint> {
+            match value {
+                Some(value) if value <= <$uint>::MAX as u128 => Some(value as $uint),
+                _ => None,
+            }
+        }
+
+        /// `n!`
+        pub const fn $factorial(n: u8) -> Option<$uint> {
+            $narrow(lookup(&FACTORIALS, n))
+        }
+
+        /// `n! / (n - k)!`, the number of arrangements of `k` among `n`, `0` if `k > n`.
+        pub const fn $falling_factorial(n: u8, k: u8) -> Option<$uint> {
+            $narrow(falling_factorial(n, k))
+        }
+
+        /// `n` choose `k`, `0` if `k > n`.
+        pub const fn $binomial(n: u8, k: u8) -> Option<$uint> {
+            $narrow(binomial(n as u32, k as u32))
+        }
+
+        /// `(sum parts)! / prod(part!)`, the number of anagrams with the given letter counts.
+        pub const fn $multinomial(parts: &[u8]) -> Option<$uint> {
+            $narrow(multinomial(parts))
+        }
+
+        /// `!n`, the number of derangements.
+        pub const fn $subfactorial(n: u8) -> Option<$uint> {
+            $narrow(lookup(&SUBFACTORIALS, n))
+        }
+
+        /// The unsigned Stirling number of the first kind, permutations of `n` elements with `k` cycles.
+        pub const fn $stirling_first(n: u8, k: u8) -> Option<$uint> {
+            $narrow(stirling(true, n, k))
+        }
+
+        /// The Stirling number of the second kind, partitions of `n` elements into `k` blocks.
+        pub const fn $stirling_second(n: u8, k: u8) -> Option<$uint> {
+            $narrow(stirling(false, n, k))
+        }
+
+        /// The Bell number, partitions of `n` elements.
+        pub const fn $bell(n: u8) -> Option<$uint> {
+            $narrow(lookup(&BELL_NUMBERS, n))
+        }
+
+        /// The Catalan number, e.g. the permutations of `n` elements avoiding a pattern of length 3.
+        pub const fn $catalan(n: u8) -> Option<$uint> {
+            $narrow(lookup(&CATALAN_NUMBERS, n))
+        }
+
+        /// The Euler zigzag number, alternating permutations of `n` elements starting with an ascent.
+        pub const fn $euler(n: u8) -> Option<$uint> {
+            $narrow(lookup(&EULER_NUMBERS, n))
+        }
+    };
+}
+
+impl_numbers!(
+    u16,
+    narrow16,
+    factorial16,
+    falling_factorial16,
+    binomial16,
+    multinomial16,
+    subfactorial16,
+    stirling_first16,
+    stirling_second16,
+    bell16,
+    catalan16,
+    euler16
+);
+impl_numbers!(
+    u64,
+    narrow64,
+    factorial64,
+    falling_factorial64,
+    binomial64,
+    multinomial64,
+    subfactorial64,
+    stirling_first64,
+    stirling_second64,
+    bell64,
+    catalan64,
+    euler64
+);
+impl_numbers!(
+    u128,
+    narrow128,
+    factorial128,
+    falling_factorial128,
+    binomial128,
+    multinomial128,
+    subfactorial128,
+    stirling_first128,
+    stirling_second128,
+    bell128,
+    catalan128,
+    euler128
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn factorials() {
+        assert_eq!(Some(1), factorial16(0));
+        assert_eq!(Some(40320), factorial16(8));
+        assert_eq!(None, factorial16(9));
+        assert_eq!(Some(2432902008176640000), factorial64(20));
+        assert_eq!(None, factorial64(21));
+        assert_eq!(
+            Some(295232799039604140847618609643520000000),
+            factorial128(34)
+        );
+        assert_eq!(None, factorial128(35));
+        assert_eq!(None, factorial128(255));
+        assert_eq!(Some(5040), falling_factorial16(10, 4));
+        assert_eq!(Some(0), falling_factorial16(3, 4));
+        assert_eq!(Some(1), falling_factorial16(255, 0));
+        assert_eq!(Some(255 * 254 * 253 * 252), falling_factorial64(255, 4));
+        assert_eq!(None, falling_factorial128(255, 17));
+    }
+
+    #[test]
+    fn binomials() {
+        assert_eq!(Some(1), binomial16(0, 0));
+        assert_eq!(Some(0), binomial16(3, 4));
+        assert_eq!(Some(12870), binomial16(16, 8));
+        assert_eq!(None, binomial16(20, 10));
+        assert_eq!(Some(14226520737620288370), binomial64(67, 33));
+        assert_eq!(None, binomial64(68, 34));
+        assert_eq!(
+            Some(188694833082770476622296176145946360850),
+            binomial128(131, 65)
+        );
+        assert_eq!(None, binomial128(132, 66));
+        assert_eq!(Some(255), binomial128(255, 254));
+        assert_eq!(Some(210), multinomial16(&[3, 2, 2]));
+        assert_eq!(Some(256), multinomial64(&[255, 1]));
+        assert_eq!(Some(1), multinomial64(&[]));
+        assert_eq!(None, multinomial16(&[5, 5, 5]));
+    }
+
+    #[test]
+    fn permutation_counts() {
+        assert_eq!(Some(14833), subfactorial16(8));
+        assert_eq!(
+            Some(96800425246141091510518408809597121),
+            subfactorial128(32)
+        );
+        assert_eq!(
+            Some(108610077126170304674801654684367969729),
+            subfactorial128(34)
+        );
+        assert_eq!(None, subfactorial128(35));
+        assert_eq!(Some(23489580527043108252017828576198947741), euler128(38));
+        assert_eq!(None, euler128(39));
+        assert_eq!(
+            Some(337485502510215975556783793455058624700),
+            catalan128(69)
+        );
+        assert_eq!(None, catalan128(70));
+        assert_eq!(Some(1385), euler16(8));
+        assert_eq!(Some(19391512145), euler64(16));
+        assert_eq!(Some(58786), catalan16(11));
+        assert_eq!(None, catalan16(12));
+        assert_eq!(Some(13132), stirling_first16(8, 3));
+        assert_eq!(Some(1), stirling_first128(200, 200));
+        assert_eq!(Some(19900), stirling_first128(200, 199));
+        assert_eq!(None, stirling_first128(200, 1));
+        assert_eq!(Some(0), stirling_first16(5, 6));
+        assert_eq!(Some(0), stirling_first16(5, 0));
+        assert_eq!(
+            Some(49361465831621147825759587123200),
+            stirling_first128(30, 5)
+        );
+        assert_eq!(
+            Some(1083606530591509770261650763430379569),
+            stirling_first128(40, 20)
+        );
+        assert_eq!(
+            Some(1303715598515701800848151955950),
+            stirling_first128(100, 90)
+        );
+        assert_eq!(
+            Some(275373118058813412850153974350846516625),
+            stirling_first128(255, 245)
+        );
+        assert_eq!(None, stirling_first128(50, 25));
+    }
+
+    #[test]
+    fn partitions() {
+        assert_eq!(Some(4140), bell16(8));
+        assert_eq!(Some(1), bell16(0));
+        assert_eq!(Some(35742549198872617291353508656626642567), bell128(42));
+        assert_eq!(None, bell128(43));
+        assert_eq!(Some(25), stirling_second16(5, 3));
+        assert_eq!(Some(1), stirling_second16(0, 0));
+        assert_eq!(
+            Some(bell128(40).unwrap()),
+            (0..=40).try_fold(0u128, |sum, k| Some(sum + stirling_second128(40, k)?))
+        );
+        assert_eq!(Some(2u128.pow(99) - 1), stirling_second128(100, 2));
+        assert_eq!(None, stirling_second128(100, 50));
+        assert_eq!(Some(119649664052358811373730), stirling_second128(50, 3));
+        assert_eq!(
+            Some(162188909527975750487887236507181),
+            stirling_second128(40, 20)
+        );
+        assert_eq!(
+            Some(216608919112665020268694270201285624000),
+            stirling_second128(255, 245)
+        );
+        assert_eq!(None, stirling_second128(120, 4));
+    }
+
+    #[test]
+    fn const_evaluation() {
+        const FACTORIAL: Option<u64> = factorial64(20);
+        const MULTINOMIAL: Option<u64> = multinomial64(&[2, 3, 4]);
+        const STIRLING: Option<u128> = stirling_second128(60, 3);
+        assert_eq!(Some(2432902008176640000), FACTORIAL);
+        assert_eq!(Some(1260), MULTINOMIAL);
+        assert_eq!(Some((3u128.pow(60) - 3 * 2u128.pow(60) + 3) / 6), STIRLING);
+    }
+}
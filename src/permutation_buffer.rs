@@ -0,0 +1,128 @@
+use std::ops::{Deref, DerefMut};
+
+/// A decoded permutation of upto 32 elements, stored inline.
+///
+/// Iterating consumes the elements from the front, `as_slice` gives the elements not consumed yet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PermutationBuffer {
+    elems: [u8; 32],
+    len: u8,
+    pos: u8,
+}
+
+impl PermutationBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Panics if the buffer already holds 32 elements.
+    #[inline]
+    pub fn push(&mut self, elem: u8) {
+        self.elems[self.len as usize] = elem;
+        self.len += 1;
+    }
+
+    /// Removes the last element, `None` if no element is left.
+    #[inline]
+    pub fn pop(&mut self) -> Option<u8> {
+        if self.len == self.pos {
+            return None;
+        }
+        self.len -= 1;
+        Some(self.elems[self.len as usize])
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.pos = 0;
+    }
+
+    /// The number of elements not consumed yet.
+    #[inline]
+    pub fn len(&self) -> usize {
+        (self.len - self.pos) as usize
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == self.pos
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.elems[self.pos as usize..self.len as usize]
+    }
+
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.elems[self.pos as usize..self.len as usize]
+    }
+}
+
+impl Deref for PermutationBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl DerefMut for PermutationBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl Iterator for PermutationBuffer {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos == self.len {
+            return None;
+        }
+        self.pos += 1;
+        Some(self.elems[self.pos as usize - 1])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let nb_remaining = PermutationBuffer::len(self);
+        (nb_remaining, Some(nb_remaining))
+    }
+}
+
+impl ExactSizeIterator for PermutationBuffer {}
+
+impl FromIterator<u8> for PermutationBuffer {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        let mut buffer = Self::new();
+        iter.into_iter().for_each(|elem| buffer.push(elem));
+        buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push() {
+        let mut buffer = PermutationBuffer::new();
+        buffer.push(2);
+        buffer.push(0);
+        buffer.push(1);
+        assert_eq!(&[2, 0, 1], buffer.as_slice());
+        assert_eq!(3, buffer.len());
+        buffer.clear();
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn iter() {
+        let mut buffer = (0..4).rev().collect::<PermutationBuffer>();
+        assert_eq!(Some(3), buffer.next());
+        assert_eq!(&[2, 1, 0], &*buffer);
+        assert_eq!(3, buffer.len());
+        assert_eq!(vec![2, 1, 0], buffer.collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,200 @@
+use super::*;
+
+/// Stack storage for a single decoded permutation of the widest tier.
+const BUFFER_LEN: usize = <u128 as Rank>::MAX_ELEMENTS as usize;
+
+impl<R: Rank> PermutationGenerator<R> {
+    /// Rearranges `data` into its `idx`-th permutation, such that `data[i]` becomes the element at index `perm[i]`.
+    ///
+    /// Returns `Ok(false)`, leaving `data` untouched, if `idx` is out of range.
+    pub fn apply_nth_absolute<T>(data: &mut [T], idx: R) -> PResult<bool> {
+        Self::decode_for(data, idx).map(|decoded| match decoded {
+            Some((buffer, nb_elems)) => {
+                gather(data, &buffer[..nb_elems]);
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Undoes `apply_nth_absolute`: the element at index `i` of `data` moves to index `perm[i]`.
+    pub fn apply_inverse_nth_absolute<T>(data: &mut [T], idx: R) -> PResult<bool> {
+        Self::decode_for(data, idx).map(|decoded| match decoded {
+            Some((buffer, nb_elems)) => {
+                scatter(data, &buffer[..nb_elems]);
+                true
+            }
+            None => false,
+        })
+    }
+
+    /// Rearranges the first `perm.len()` elements of `data` such that `data[i]` becomes the element at index `perm[i]`.
+    pub fn apply_in_place<T>(data: &mut [T], perm: &[u8]) -> PResult<()> {
+        Self::check_apply(data, perm).map(|_| gather(&mut data[..perm.len()], perm))
+    }
+
+    /// Undoes `apply_in_place`: the element at index `i` of `data` moves to index `perm[i]`.
+    pub fn apply_inverse_in_place<T>(data: &mut [T], perm: &[u8]) -> PResult<()> {
+        Self::check_apply(data, perm).map(|_| scatter(&mut data[..perm.len()], perm))
+    }
+
+    fn decode_for<T>(data: &[T], idx: R) -> PResult<Option<([u8; BUFFER_LEN], usize)>> {
+        let nb_elems = Self::check_len(data.len())?;
+        Ok(
+            R::permutation(nb_elems, R::factorial(nb_elems), idx).map(|perm| {
+                let mut buffer = [0; BUFFER_LEN];
+                buffer
+                    .iter_mut()
+                    .zip(perm)
+                    .for_each(|(slot, elem)| *slot = elem);
+                (buffer, nb_elems as usize)
+            }),
+        )
+    }
+
+    fn check_apply<T>(data: &[T], perm: &[u8]) -> PResult<()> {
+        if perm.len() > R::MAX_ELEMENTS as usize {
+            Err(PermutationGeneratorError::TooManyElements)
+        } else if data.len() < perm.len() {
+            Err(PermutationGeneratorError::SliceTooSmall)
+        } else {
+            check_permutation(perm)
+        }
+    }
+}
+
+/// Checks that `perm` holds every index of `0..perm.len()` exactly once, with `perm.len() <= 32`.
+pub(crate) fn check_permutation(perm: &[u8]) -> PResult<()> {
+    let mut seen = 0u64;
+    for &elem in perm {
+        if elem as usize >= perm.len() || seen & (1 << elem) != 0 {
+            return Err(PermutationGeneratorError::NotAPermutation);
+        }
+        seen |= 1 << elem;
+    }
+    Ok(())
+}
+
+/// `data[i] <- data[perm[i]]`, following each cycle of `perm` once.
+fn gather<T>(data: &mut [T], perm: &[u8]) {
+    let mut visited = 0u64;
+    for start in 0..perm.len() {
+        if visited & (1 << start) != 0 {
+            continue;
+        }
+        let mut current = start;
+        visited |= 1 << current;
+        while perm[current] as usize != start {
+            let next = perm[current] as usize;
+            data.swap(current, next);
+            visited |= 1 << next;
+            current = next;
+        }
+    }
+}
+
+/// `data[perm[i]] <- data[i]`, following each cycle of `perm` once.
+fn scatter<T>(data: &mut [T], perm: &[u8]) {
+    let mut visited = 0u64;
+    for start in 0..perm.len() {
+        if visited & (1 << start) != 0 {
+            continue;
+        }
+        visited |= 1 << start;
+        let mut next = perm[start] as usize;
+        while next != start {
+            data.swap(start, next);
+            visited |= 1 << next;
+            next = perm[next] as usize;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_nth_absolute() {
+        let mut data = ['a', 'b', 'c', 'd'];
+        assert_eq!(
+            Ok(true),
+            PermutationGenerator8::apply_nth_absolute(&mut data, 23)
+        );
+        assert_eq!(['d', 'c', 'b', 'a'], data);
+
+        let mut data = ['a', 'b', 'c', 'd'];
+        assert_eq!(
+            Ok(true),
+            PermutationGenerator16::apply_nth_absolute(&mut data, 6)
+        );
+        assert_eq!(['b', 'a', 'c', 'd'], data);
+
+        let mut data = ['a', 'b', 'c', 'd'];
+        assert_eq!(
+            Ok(false),
+            PermutationGenerator32::apply_nth_absolute(&mut data, 24)
+        );
+        assert_eq!(['a', 'b', 'c', 'd'], data);
+
+        let mut data = [0; 9];
+        assert_eq!(
+            Err(PermutationGeneratorError::TooManyElements),
+            PermutationGenerator8::apply_nth_absolute(&mut data, 0)
+        );
+    }
+
+    #[test]
+    fn apply_in_place() {
+        let mut data = ["foo", "bar", "baz", "qux"];
+        PermutationGenerator8::apply_in_place(&mut data, &[1, 2, 0]).unwrap();
+        assert_eq!(["bar", "baz", "foo", "qux"], data);
+        PermutationGenerator8::apply_inverse_in_place(&mut data, &[1, 2, 0]).unwrap();
+        assert_eq!(["foo", "bar", "baz", "qux"], data);
+
+        assert_eq!(
+            Err(PermutationGeneratorError::SliceTooSmall),
+            PermutationGenerator8::apply_in_place(&mut data, &[0, 1, 2, 3, 4])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            PermutationGenerator8::apply_in_place(&mut data, &[0, 1, 1])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            PermutationGenerator8::apply_in_place(&mut data, &[0, 3, 1])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::TooManyElements),
+            PermutationGenerator8::apply_in_place(&mut [0; 9], &[0, 1, 2, 3, 4, 5, 6, 7, 8])
+        );
+    }
+
+    #[test]
+    fn matches_decoded_permutation() {
+        for idx in 0..factorial16(5) {
+            let perm = PermutationGenerator8::nth_absolute(5, idx)
+                .unwrap()
+                .unwrap()
+                .collect::<Vec<_>>();
+            let mut data = [10, 11, 12, 13, 14];
+            PermutationGenerator8::apply_nth_absolute(&mut data, idx).unwrap();
+            assert_eq!(
+                perm.iter().map(|&i| 10 + i).collect::<Vec<_>>().as_slice(),
+                &data
+            );
+            PermutationGenerator8::apply_inverse_nth_absolute(&mut data, idx).unwrap();
+            assert_eq!([10, 11, 12, 13, 14], data);
+        }
+    }
+
+    #[test]
+    fn widest_tier() {
+        let mut data = (0..32).collect::<Vec<u8>>();
+        let idx = factorial128(32) - 1;
+        PermutationGenerator32::apply_nth_absolute(&mut data, idx).unwrap();
+        assert_eq!((0..32).rev().collect::<Vec<_>>(), data);
+        PermutationGenerator32::apply_inverse_nth_absolute(&mut data, idx).unwrap();
+        assert_eq!((0..32).collect::<Vec<_>>(), data);
+    }
+}
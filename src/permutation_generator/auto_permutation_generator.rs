@@ -0,0 +1,189 @@
+use super::*;
+
+/// A `PermutationGenerator` on the narrowest tier able to hold `nb_elems` elements.
+///
+/// Indexes are taken as `u128` and narrowed to the rank type of the selected tier.
+#[derive(Clone, Copy, Debug)]
+pub enum AutoPermutationGenerator {
+    Tier8(PermutationGenerator8),
+    Tier16(PermutationGenerator16),
+    Tier32(PermutationGenerator32),
+}
+
+/// A single permutation decoded on the tier selected by `AutoPermutationGenerator`.
+#[derive(Clone, Debug)]
+pub struct AutoPermutation(TieredPermutation);
+
+#[derive(Clone, Debug)]
+enum TieredPermutation {
+    Tier8(<u16 as Rank>::Permutation),
+    Tier16(<u64 as Rank>::Permutation),
+    Tier32(<u128 as Rank>::Permutation),
+}
+
+impl AutoPermutationGenerator {
+    pub fn new(nb_elems: u8) -> PResult<Self> {
+        match nb_elems {
+            0..=8 => PermutationGenerator8::new(nb_elems).map(Self::Tier8),
+            9..=16 => PermutationGenerator16::new(nb_elems).map(Self::Tier16),
+            _ => PermutationGenerator32::new(nb_elems).map(Self::Tier32),
+        }
+    }
+
+    pub fn next_permutation(&mut self) -> Option<AutoPermutation> {
+        self.nth(0)
+    }
+
+    pub fn nth_absolute(nb_elems: u8, idx: u128) -> PResult<Option<AutoPermutation>> {
+        match nb_elems {
+            0..=8 => tiered_absolute::<u16>(nb_elems, idx)
+                .map(|res| res.map(|perm| AutoPermutation(TieredPermutation::Tier8(perm)))),
+            9..=16 => tiered_absolute::<u64>(nb_elems, idx)
+                .map(|res| res.map(|perm| AutoPermutation(TieredPermutation::Tier16(perm)))),
+            _ => tiered_absolute::<u128>(nb_elems, idx)
+                .map(|res| res.map(|perm| AutoPermutation(TieredPermutation::Tier32(perm)))),
+        }
+    }
+
+    pub fn nth(&mut self, step: u128) -> Option<AutoPermutation> {
+        match self {
+            Self::Tier8(pg) => {
+                tiered_nth(pg, step).map(|perm| AutoPermutation(TieredPermutation::Tier8(perm)))
+            }
+            Self::Tier16(pg) => {
+                tiered_nth(pg, step).map(|perm| AutoPermutation(TieredPermutation::Tier16(perm)))
+            }
+            Self::Tier32(pg) => {
+                tiered_nth(pg, step).map(|perm| AutoPermutation(TieredPermutation::Tier32(perm)))
+            }
+        }
+    }
+
+    /// Panics if the number of remaining permutations overflows `usize`, e.g. on nb_elems > 20
+    pub fn nb_remaining(&self) -> usize {
+        match self {
+            Self::Tier8(pg) => pg.nb_remaining(),
+            Self::Tier16(pg) => pg.nb_remaining(),
+            Self::Tier32(pg) => pg.nb_remaining(),
+        }
+    }
+}
+
+/// Steps that do not fit the rank type of the tier are past the last permutation anyway.
+#[inline]
+fn tiered_nth<R: Rank>(pg: &mut PermutationGenerator<R>, step: u128) -> Option<R::Permutation> {
+    let step_result = pg
+        .next_idx
+        .saturating_add(R::from_u128(step).unwrap_or(R::MAX));
+    let res = R::permutation(pg.nb_elems, pg.nb_perms, step_result);
+    pg.next_idx = step_result.saturating_add(R::ONE);
+    res
+}
+
+#[inline]
+fn tiered_absolute<R: Rank>(nb_elems: u8, idx: u128) -> PResult<Option<R::Permutation>> {
+    PermutationGenerator::<R>::check_nb_elems(nb_elems).map(|_| {
+        R::from_u128(idx).and_then(|idx| R::permutation(nb_elems, R::factorial(nb_elems), idx))
+    })
+}
+
+impl Iterator for AutoPermutationGenerator {
+    type Item = AutoPermutation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_permutation()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let nb_remaining = self.nb_remaining();
+        (nb_remaining, Some(nb_remaining))
+    }
+
+    fn count(self) -> usize {
+        self.nb_remaining()
+    }
+}
+
+impl Iterator for AutoPermutation {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            TieredPermutation::Tier8(perm) => perm.next(),
+            TieredPermutation::Tier16(perm) => perm.next(),
+            TieredPermutation::Tier32(perm) => perm.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            TieredPermutation::Tier8(perm) => perm.size_hint(),
+            TieredPermutation::Tier16(perm) => perm.size_hint(),
+            TieredPermutation::Tier32(perm) => perm.size_hint(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_iter: Option<impl Iterator<Item = u8>>) {
+        assert_eq!(ref_slice, some_iter.unwrap().collect::<Vec<_>>().as_slice());
+    }
+
+    #[test]
+    fn new() {
+        assert!(matches!(
+            AutoPermutationGenerator::new(4),
+            Ok(AutoPermutationGenerator::Tier8(_))
+        ));
+        assert!(matches!(
+            AutoPermutationGenerator::new(9),
+            Ok(AutoPermutationGenerator::Tier16(_))
+        ));
+        assert!(matches!(
+            AutoPermutationGenerator::new(17),
+            Ok(AutoPermutationGenerator::Tier32(_))
+        ));
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            AutoPermutationGenerator::new(33).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[3, 2, 1, 0],
+            AutoPermutationGenerator::nth_absolute(4, 23).unwrap(),
+        );
+        test_slice(
+            &[1, 0, 2, 3, 4, 5, 6, 7, 8],
+            AutoPermutationGenerator::nth_absolute(9, factorial128(8)).unwrap(),
+        );
+        assert!(AutoPermutationGenerator::nth_absolute(4, u128::MAX)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn nth() {
+        let mut pg = AutoPermutationGenerator::new(4).unwrap();
+        test_slice(&[0, 1, 2, 3], pg.next_permutation());
+        test_slice(&[3, 2, 1, 0], pg.nth(22));
+        assert!(pg.next_permutation().is_none());
+
+        let mut pg = AutoPermutationGenerator::new(4).unwrap();
+        assert!(pg.nth(u128::MAX).is_none());
+        assert_eq!(0, pg.nb_remaining());
+    }
+
+    #[test]
+    fn iter() {
+        let pg = AutoPermutationGenerator::new(5).unwrap();
+        assert_eq!(120, pg.count());
+        let last = AutoPermutationGenerator::new(5).unwrap().last().unwrap();
+        assert_eq!(&[4, 3, 2, 1, 0], last.collect::<Vec<_>>().as_slice());
+    }
+}
@@ -1,9 +1,157 @@
 use super::*;
 
+mod apply;
+mod auto_permutation_generator;
 mod permutation_generator_16;
 mod permutation_generator_32;
 mod permutation_generator_8;
 
+pub(crate) use apply::check_permutation;
+pub use auto_permutation_generator::{AutoPermutation, AutoPermutationGenerator};
 pub use permutation_generator_16::PermutationGenerator16;
 pub use permutation_generator_32::PermutationGenerator32;
 pub use permutation_generator_8::PermutationGenerator8;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PermutationGenerator<R: Rank> {
+    nb_elems: u8,
+    nb_perms: R,
+    next_idx: R,
+}
+
+impl<R: Rank> PermutationGenerator<R> {
+    pub fn new(nb_elems: u8) -> PResult<Self> {
+        Self::check_nb_elems(nb_elems).map(|_| Self {
+            next_idx: R::ZERO,
+            nb_perms: R::factorial(nb_elems),
+            nb_elems,
+        })
+    }
+
+    pub fn next_permutation(&mut self) -> Option<impl Iterator<Item = u8>> {
+        self.nth(R::ZERO)
+    }
+
+    pub fn nth_absolute(nb_elems: u8, idx: R) -> PResult<Option<impl Iterator<Item = u8>>> {
+        Self::check_nb_elems(nb_elems)
+            .map(|_| R::permutation(nb_elems, R::factorial(nb_elems), idx))
+    }
+
+    pub fn nth(&mut self, step: R) -> Option<impl Iterator<Item = u8>> {
+        let step_result = self.next_idx.saturating_add(step);
+        let res = R::permutation(self.nb_elems, self.nb_perms, step_result);
+        self.next_idx = step_result.saturating_add(R::ONE);
+        res
+    }
+
+    /// The index of `perm` among the permutations of `perm.len()` elements, the inverse of `nth_absolute`.
+    ///
+    /// Like `nth_absolute`, there is no permutation of zero elements.
+    pub fn rank(perm: &[u8]) -> PResult<R> {
+        match Self::check_len(perm.len())? {
+            0 => Err(PermutationGeneratorError::NotAPermutation),
+            nb_elems => lehmer_rank(nb_elems, perm.iter().copied()),
+        }
+    }
+
+    /// Panics if the number of remaining permutations overflows `usize`, e.g. on nb_elems > 20
+    pub fn nb_remaining(&self) -> usize {
+        if self.next_idx >= self.nb_perms {
+            return 0;
+        }
+        match (self.nb_perms - self.next_idx).to_usize() {
+            Some(nb) => nb,
+            None => panic!("The size of the iterator overflowed usize"),
+        }
+    }
+
+    #[inline]
+    fn check_nb_elems(nb_elems: u8) -> PResult<()> {
+        if nb_elems > R::MAX_ELEMENTS {
+            Err(PermutationGeneratorError::TooManyElements)
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    fn check_len(len: usize) -> PResult<u8> {
+        match u8::try_from(len) {
+            Ok(nb_elems) if nb_elems <= R::MAX_ELEMENTS => Ok(nb_elems),
+            _ => Err(PermutationGeneratorError::TooManyElements),
+        }
+    }
+}
+
+/// The lexicographic index of the permutation of `0..nb_elems` listing `elems` in order.
+pub(crate) fn lehmer_rank<R: Rank>(
+    nb_elems: u8,
+    elems: impl IntoIterator<Item = u8>,
+) -> PResult<R> {
+    let mut remaining = (1u64 << nb_elems) - 1;
+    let mut nb_remaining = nb_elems;
+    let mut rank = R::ZERO;
+    for elem in elems {
+        if elem >= nb_elems || remaining & (1 << elem) == 0 {
+            return Err(PermutationGeneratorError::NotAPermutation);
+        }
+        let digit = (remaining & ((1 << elem) - 1)).count_ones() as u8;
+        rank = rank * R::from(nb_remaining) + R::from(digit);
+        remaining &= !(1 << elem);
+        nb_remaining -= 1;
+    }
+    if nb_remaining == 0 {
+        Ok(rank)
+    } else {
+        Err(PermutationGeneratorError::NotAPermutation)
+    }
+}
+
+/// The permutation of `PermutationGenerator::nth_absolute(nb_elems, idx)` as an array, usable in constants, e.g.
+/// `const P: [u8; 5] = nth_const(5, 77);`.
+///
+/// Panics, at compile time for a constant, if `N != nb_elems` or if there is no such permutation.
+pub const fn nth_const<const N: usize>(nb_elems: u8, mut idx: u128) -> [u8; N] {
+    assert!(
+        N == nb_elems as usize,
+        "The array length differs from nb_elems"
+    );
+    assert!(
+        nb_elems <= u128::MAX_ELEMENTS && idx < factorial128(nb_elems),
+        "No permutation with this index"
+    );
+    let mut perm = [0; N];
+    let mut remaining = (1u64 << nb_elems) - 1;
+    let mut pos = 0;
+    while pos < N {
+        let place = FACTORIALS128[N - 1 - pos];
+        let mut smaller = remaining;
+        let mut digit = idx / place;
+        idx %= place;
+        while digit > 0 {
+            smaller &= smaller - 1;
+            digit -= 1;
+        }
+        perm[pos] = smaller.trailing_zeros() as u8;
+        remaining &= !(1 << perm[pos]);
+        pos += 1;
+    }
+    perm
+}
+
+impl<R: Rank> Iterator for PermutationGenerator<R> {
+    type Item = impl Iterator<Item = u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_permutation()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let nb_remaining = self.nb_remaining();
+        (nb_remaining, Some(nb_remaining))
+    }
+
+    fn count(self) -> usize {
+        self.nb_remaining()
+    }
+}
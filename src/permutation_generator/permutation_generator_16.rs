@@ -1,69 +1,6 @@
 use super::*;
 
-#[derive(Clone, Copy, Debug)]
-pub struct PermutationGenerator16 {
-    nb_elems: u8,
-    nb_perms: u64,
-    next_idx: u64,
-}
-
-impl PermutationGenerator16 {
-    const MAX_ELEMENTS: u8 = 16;
-
-    pub fn new(nb_elems: u8) -> PResult<Self> {
-        Self::check_nb_elems(nb_elems).map(|_| Self {
-            next_idx: 0,
-            nb_perms: factorial64(nb_elems),
-            nb_elems,
-        })
-    }
-
-    pub fn next_permutation(&mut self) -> Option<impl Iterator<Item = u8>> {
-        self.nth(0)
-    }
-
-    pub fn nth_absolute(nb_elems: u8, idx: u64) -> PResult<Option<impl Iterator<Item = u8>>> {
-        Self::check_nb_elems(nb_elems)
-            .map(|_| SinglePermutation16::new(nb_elems, factorial64(nb_elems), idx))
-    }
-
-    pub fn nth(&mut self, step: u64) -> Option<impl Iterator<Item = u8>> {
-        let step_result = self.next_idx.saturating_add(step);
-        let res = SinglePermutation16::new(self.nb_elems, self.nb_perms, step_result);
-        self.next_idx = step_result + 1;
-        res
-    }
-
-    pub fn nb_remaining(&self) -> usize {
-        (self.nb_perms - self.next_idx) as usize
-    }
-
-    #[inline]
-    fn check_nb_elems(nb_elems: u8) -> PResult<()> {
-        if nb_elems > Self::MAX_ELEMENTS {
-            Err(PermutationGeneratorError::TooManyElements)
-        } else {
-            Ok(())
-        }
-    }
-}
-
-impl Iterator for PermutationGenerator16 {
-    type Item = impl Iterator<Item = u8>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.next_permutation()
-    }
-
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let nb_remaining = self.nb_remaining();
-        (nb_remaining, Some(nb_remaining))
-    }
-
-    fn count(self) -> usize {
-        self.nb_remaining()
-    }
-}
+pub type PermutationGenerator16 = PermutationGenerator<u64>;
 
 #[cfg(test)]
 mod tests {
@@ -121,6 +58,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rank() {
+        assert_eq!(
+            Ok(factorial64(NB_ELEMS) - 1),
+            PermutationGenerator16::rank(&[8, 7, 6, 5, 4, 3, 2, 1, 0])
+        );
+        assert_eq!(
+            Ok(factorial64(NB_ELEMS - 1)),
+            PermutationGenerator16::rank(&[1, 0, 2, 3, 4, 5, 6, 7, 8])
+        );
+    }
+
     #[test]
     fn iter() {
         let iter = PermutationGenerator16::new(NB_ELEMS).unwrap();
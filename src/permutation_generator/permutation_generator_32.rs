@@ -1,73 +1,6 @@
 use super::*;
 
-#[derive(Clone, Copy, Debug)]
-pub struct PermutationGenerator32 {
-    nb_elems: u8,
-    nb_perms: u128,
-    next_idx: u128,
-}
-
-impl PermutationGenerator32 {
-    const MAX_ELEMENTS: u8 = 32;
-
-    pub fn new(nb_elems: u8) -> PResult<Self> {
-        Self::check_nb_elems(nb_elems).map(|_| Self {
-            next_idx: 0,
-            nb_perms: factorial128(nb_elems),
-            nb_elems,
-        })
-    }
-
-    pub fn next_permutation(&mut self) -> Option<impl Iterator<Item = u8>> {
-        self.nth(0)
-    }
-
-    pub fn nth_absolute(nb_elems: u8, idx: u128) -> PResult<Option<impl Iterator<Item = u8>>> {
-        Self::check_nb_elems(nb_elems)
-            .map(|_| SinglePermutation32::new(nb_elems, factorial128(nb_elems), idx))
-    }
-
-    pub fn nth(&mut self, step: u128) -> Option<impl Iterator<Item = u8>> {
-        let step_result = self.next_idx.saturating_add(step);
-        let res = SinglePermutation32::new(self.nb_elems, self.nb_perms, step_result);
-        self.next_idx = step_result + 1;
-        res
-    }
-
-    /// Panics on nb_elems > 20
-    pub fn nb_remaining(&self) -> usize {
-        match (self.nb_perms - self.next_idx).try_into() {
-            Ok(nb) => nb,
-            Err(_) => panic!("The size of the iterator owerflowed usize"),
-        }
-    }
-
-    #[inline]
-    fn check_nb_elems(nb_elems: u8) -> PResult<()> {
-        if nb_elems > Self::MAX_ELEMENTS {
-            Err(PermutationGeneratorError::TooManyElements)
-        } else {
-            Ok(())
-        }
-    }
-}
-
-impl Iterator for PermutationGenerator32 {
-    type Item = impl Iterator<Item = u8>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.next_permutation()
-    }
-
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let nb_remaining = self.nb_remaining();
-        (nb_remaining, Some(nb_remaining))
-    }
-
-    fn count(self) -> usize {
-        self.nb_remaining()
-    }
-}
+pub type PermutationGenerator32 = PermutationGenerator<u128>;
 
 #[cfg(test)]
 mod tests {
@@ -129,6 +62,19 @@ mod tests {
         assert!(pg.next_permutation().is_none());
     }
 
+    #[test]
+    fn rank() {
+        let perm = (0..30).rev().collect::<Vec<_>>();
+        assert_eq!(
+            Ok(factorial128(30) - 1),
+            PermutationGenerator32::rank(&perm)
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            PermutationGenerator32::rank(&perm[..29])
+        );
+    }
+
     #[test]
     fn iter() {
         let iter = PermutationGenerator32::new(NB_ELEMS).unwrap();
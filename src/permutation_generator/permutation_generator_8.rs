@@ -1,69 +1,6 @@
 use super::*;
 
-#[derive(Clone, Copy, Debug)]
-pub struct PermutationGenerator8 {
-    nb_elems: u8,
-    nb_perms: u16,
-    next_idx: u16,
-}
-
-impl PermutationGenerator8 {
-    const MAX_ELEMENTS: u8 = 8;
-
-    pub fn new(nb_elems: u8) -> PResult<Self> {
-        Self::check_nb_elems(nb_elems).map(|_| Self {
-            next_idx: 0,
-            nb_perms: factorial16(nb_elems),
-            nb_elems,
-        })
-    }
-
-    pub fn next_permutation(&mut self) -> Option<impl Iterator<Item = u8>> {
-        self.nth(0)
-    }
-
-    pub fn nth_absolute(nb_elems: u8, idx: u16) -> PResult<Option<impl Iterator<Item = u8>>> {
-        Self::check_nb_elems(nb_elems)
-            .map(|_| SinglePermutation8::new(nb_elems, factorial16(nb_elems), idx))
-    }
-
-    pub fn nth(&mut self, step: u16) -> Option<impl Iterator<Item = u8>> {
-        let step_result = self.next_idx.saturating_add(step);
-        let res = SinglePermutation8::new(self.nb_elems, self.nb_perms, step_result);
-        self.next_idx = step_result + 1;
-        res
-    }
-
-    pub fn nb_remaining(&self) -> usize {
-        (self.nb_perms - self.next_idx) as usize
-    }
-
-    #[inline]
-    fn check_nb_elems(nb_elems: u8) -> PResult<()> {
-        if nb_elems > Self::MAX_ELEMENTS {
-            Err(PermutationGeneratorError::TooManyElements)
-        } else {
-            Ok(())
-        }
-    }
-}
-
-impl Iterator for PermutationGenerator8 {
-    type Item = impl Iterator<Item = u8>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.next_permutation()
-    }
-
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        let nb_remaining = self.nb_remaining();
-        (nb_remaining, Some(nb_remaining))
-    }
-
-    fn count(self) -> usize {
-        self.nb_remaining()
-    }
-}
+pub type PermutationGenerator8 = PermutationGenerator<u16>;
 
 #[cfg(test)]
 mod tests {
@@ -115,6 +52,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn nth_const() {
+        const P: [u8; 5] = super::nth_const(5, 77);
+        test_slice(&P, PermutationGenerator8::nth_absolute(5, 77).unwrap());
+        for idx in 0..factorial16(NB_ELEMS + 1) {
+            test_slice(
+                &super::nth_const::<5>(NB_ELEMS + 1, idx as u128),
+                PermutationGenerator8::nth_absolute(NB_ELEMS + 1, idx).unwrap(),
+            );
+        }
+        const LAST: [u8; 32] = super::nth_const(32, FACTORIALS128[32] - 1);
+        assert_eq!((0..32).rev().collect::<Vec<_>>(), LAST);
+    }
+
+    #[test]
+    #[should_panic]
+    fn nth_const_out_of_range() {
+        super::nth_const::<4>(NB_ELEMS, 24);
+    }
+
     #[test]
     fn nth() {
         let mut pg = PermutationGenerator8::new(NB_ELEMS).unwrap();
@@ -126,6 +83,35 @@ mod tests {
         test_slice(&[3, 2, 1, 0], pg.nth(factorial16(NB_ELEMS) - 2));
     }
 
+    #[test]
+    fn rank() {
+        assert_eq!(Ok(0), PermutationGenerator8::rank(&[0, 1, 2, 3]));
+        assert_eq!(Ok(6), PermutationGenerator8::rank(&[1, 0, 2, 3]));
+        assert_eq!(
+            Ok(factorial16(NB_ELEMS) - 1),
+            PermutationGenerator8::rank(&[3, 2, 1, 0])
+        );
+        for idx in 0..factorial16(NB_ELEMS + 1) {
+            let perm = PermutationGenerator8::nth_absolute(NB_ELEMS + 1, idx)
+                .unwrap()
+                .unwrap()
+                .collect::<Vec<_>>();
+            assert_eq!(Ok(idx), PermutationGenerator8::rank(&perm));
+        }
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            PermutationGenerator8::rank(&[0, 1, 1, 3])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            PermutationGenerator8::rank(&[])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::TooManyElements),
+            PermutationGenerator8::rank(&[0, 1, 2, 3, 4, 5, 6, 7, 8])
+        );
+    }
+
     #[test]
     fn iter() {
         let list = PermutationGenerator8::new(NB_ELEMS)
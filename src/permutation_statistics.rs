@@ -0,0 +1,212 @@
+use super::*;
+
+/// The usual statistics of a permutation of `0..nb_elems`, from the permutation itself or from its rank.
+///
+/// Descents are positions `i` with `p[i] > p[i + 1]`, the major index sums their 1-based positions `i + 1`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PermutationStatistics {
+    pub nb_inversions: u16,
+    pub nb_descents: u8,
+    pub major_index: u16,
+    pub nb_cycles: u8,
+    pub nb_fixed_points: u8,
+    pub longest_increasing_subsequence: u8,
+    pub is_odd: bool,
+}
+
+impl PermutationStatistics {
+    pub fn of_permutation(perm: &[u8]) -> PResult<Self> {
+        if perm.len() > u128::MAX_ELEMENTS as usize {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        let mut builder = StatisticsBuilder::new();
+        walk_permutation(perm.len() as u8, perm, |elem, remaining| {
+            builder.push(elem, (remaining & ((1 << elem) - 1)).count_ones() as u8);
+            Ok(())
+        })
+        .map(|_| builder.finish())
+    }
+
+    /// The statistics of `PermutationGenerator::nth_absolute(nb_elems, idx)`, `None` past the last permutation.
+    ///
+    /// The inversions, descents, major index and sign only use the digits of the `Factoradic`, the elements are
+    /// decoded alongside for the other statistics.
+    pub fn of_rank<R: Rank>(nb_elems: u8, idx: R) -> PResult<Option<Self>> {
+        Factoradic::from_rank(nb_elems, idx).map(|factoradic| {
+            factoradic.map(|factoradic| {
+                let mut builder = StatisticsBuilder::new();
+                factoradic
+                    .elems()
+                    .zip(factoradic.digits())
+                    .for_each(|(elem, &digit)| builder.push(elem, digit));
+                builder.finish()
+            })
+        })
+    }
+
+    /// `1` for even permutations, `-1` for odd ones.
+    pub fn sign(&self) -> i8 {
+        if self.is_odd {
+            -1
+        } else {
+            1
+        }
+    }
+}
+
+/// Accumulates the statistics one element at a time, along with its Lehmer digit.
+struct StatisticsBuilder {
+    statistics: PermutationStatistics,
+    nb_placed: u8,
+    previous_digit: Option<u8>,
+    images: [u8; 32],
+    increasing_tails: [u8; 32],
+}
+
+impl StatisticsBuilder {
+    fn new() -> Self {
+        Self {
+            statistics: PermutationStatistics::default(),
+            nb_placed: 0,
+            previous_digit: None,
+            images: [0; 32],
+            increasing_tails: [0; 32],
+        }
+    }
+
+    /// A descent between two positions is a decrease of their Lehmer digits.
+    fn push(&mut self, elem: u8, digit: u8) {
+        let pos = self.nb_placed;
+        let statistics = &mut self.statistics;
+        statistics.nb_inversions += digit as u16;
+        if self.previous_digit.is_some_and(|previous| previous > digit) {
+            statistics.nb_descents += 1;
+            statistics.major_index += pos as u16;
+        }
+        statistics.nb_fixed_points += (elem == pos) as u8;
+        let nb_tails = statistics.longest_increasing_subsequence as usize;
+        let tail_idx = self.increasing_tails[..nb_tails].partition_point(|&tail| tail < elem);
+        self.increasing_tails[tail_idx] = elem;
+        statistics.longest_increasing_subsequence += (tail_idx == nb_tails) as u8;
+        self.images[pos as usize] = elem;
+        self.previous_digit = Some(digit);
+        self.nb_placed += 1;
+    }
+
+    fn finish(mut self) -> PermutationStatistics {
+        let mut visited = 0u64;
+        for start in 0..self.nb_placed {
+            if visited & (1 << start) == 0 {
+                self.statistics.nb_cycles += 1;
+                let mut elem = start;
+                while visited & (1 << elem) == 0 {
+                    visited |= 1 << elem;
+                    elem = self.images[elem as usize];
+                }
+            }
+        }
+        self.statistics.is_odd = self.statistics.nb_inversions % 2 == 1;
+        self.statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive_nb_cycles(perm: &[u8]) -> u8 {
+        (0..perm.len())
+            .filter(|&start| {
+                let mut elem = perm[start] as usize;
+                while elem > start {
+                    elem = perm[elem] as usize;
+                }
+                elem == start
+            })
+            .count() as u8
+    }
+
+    fn naive_statistics(perm: &[u8]) -> PermutationStatistics {
+        let nb_elems = perm.len();
+        let nb_inversions = (0..nb_elems)
+            .map(|i| (i..nb_elems).filter(|&j| perm[j] < perm[i]).count() as u16)
+            .sum::<u16>();
+        let descents = (1..nb_elems).filter(|&i| perm[i - 1] > perm[i]);
+        let longest_increasing = (0..1u32 << nb_elems)
+            .filter(|&subset| {
+                let elems = (0..nb_elems).filter(|&i| subset & (1 << i) != 0);
+                let elems = elems.map(|i| perm[i]).collect::<Vec<_>>();
+                elems.windows(2).all(|pair| pair[0] < pair[1])
+            })
+            .map(|subset| subset.count_ones() as u8)
+            .max()
+            .unwrap();
+        PermutationStatistics {
+            nb_inversions,
+            nb_descents: descents.clone().count() as u8,
+            major_index: descents.map(|i| i as u16).sum(),
+            nb_cycles: naive_nb_cycles(perm),
+            nb_fixed_points: (0..nb_elems).filter(|&i| perm[i] as usize == i).count() as u8,
+            longest_increasing_subsequence: longest_increasing,
+            is_odd: nb_inversions % 2 == 1,
+        }
+    }
+
+    #[test]
+    fn of_permutation() {
+        assert_eq!(
+            Ok(PermutationStatistics {
+                nb_inversions: 4,
+                nb_descents: 2,
+                major_index: 4,
+                nb_cycles: 2,
+                nb_fixed_points: 1,
+                longest_increasing_subsequence: 2,
+                is_odd: false,
+            }),
+            PermutationStatistics::of_permutation(&[2, 1, 3, 0])
+        );
+        assert_eq!(
+            -1,
+            PermutationStatistics::of_permutation(&[1, 0])
+                .unwrap()
+                .sign()
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            PermutationStatistics::of_permutation(&[0, 0])
+        );
+    }
+
+    #[test]
+    fn matches_naive_statistics() {
+        for (idx, perm) in PermutationGenerator8::new(7).unwrap().enumerate() {
+            let perm = perm.collect::<Vec<_>>();
+            let statistics = PermutationStatistics::of_permutation(&perm).unwrap();
+            assert_eq!(naive_statistics(&perm), statistics);
+            assert_eq!(
+                Ok(Some(statistics)),
+                PermutationStatistics::of_rank(7, idx as u16)
+            );
+        }
+    }
+
+    #[test]
+    fn of_rank() {
+        let last = PermutationStatistics::of_rank(32, u128::factorial(32) - 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(496, last.nb_inversions);
+        assert_eq!(31, last.nb_descents);
+        assert_eq!(496, last.major_index);
+        assert_eq!(16, last.nb_cycles);
+        assert_eq!(0, last.nb_fixed_points);
+        assert_eq!(1, last.longest_increasing_subsequence);
+        assert_eq!(1, last.sign());
+        assert_eq!(Ok(None), PermutationStatistics::of_rank(4, 24u16));
+        assert_eq!(
+            Err(PermutationGeneratorError::TooManyElements),
+            PermutationStatistics::of_rank(9, 0u16)
+        );
+    }
+}
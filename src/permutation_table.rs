@@ -0,0 +1,164 @@
+use super::*;
+
+/// All the permutations of upto 8 elements, precomputed so that decoding and ranking are lookups.
+///
+/// Each permutation is packed in a `u32`, the element at position `i` in bits `3i..3i + 3`, 160 KB for 8 elements.
+/// The rank is split between the prefix of the first half of the elements, which fixes the most significant
+/// Lehmer digits, and the suffix, whose relative order fixes the others, each indexing a table by its packed
+/// elements.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PermutationTable8 {
+    nb_elems: u8,
+    packed: Box<[u32]>,
+    prefix_ranks: Box<[u16]>,
+    suffix_ranks: Box<[u16]>,
+}
+
+impl PermutationTable8 {
+    pub fn new(nb_elems: u8) -> PResult<Self> {
+        if nb_elems > u16::MAX_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        let nb_prefix = Self::nb_prefix(nb_elems);
+        let suffix_perms = FACTORIALS16[(nb_elems - nb_prefix) as usize];
+        let mut prefix_ranks = vec![0; 1 << (3 * nb_prefix)];
+        let mut suffix_ranks = vec![0; 1 << (3 * (nb_elems - nb_prefix))];
+        let packed = PermutationGenerator8::new(nb_elems)?
+            .enumerate()
+            .map(|(idx, perm)| {
+                let idx = idx as u16;
+                let packed = pack(perm);
+                let (prefix, suffix) = split(packed, nb_prefix);
+                prefix_ranks[prefix as usize] = idx - idx % suffix_perms;
+                suffix_ranks[suffix as usize] = idx % suffix_perms;
+                packed
+            })
+            .collect();
+        Ok(Self {
+            nb_elems,
+            packed,
+            prefix_ranks: prefix_ranks.into(),
+            suffix_ranks: suffix_ranks.into(),
+        })
+    }
+
+    pub fn nb_elems(&self) -> u8 {
+        self.nb_elems
+    }
+
+    pub fn nb_permutations(&self) -> u16 {
+        self.packed.len() as u16
+    }
+
+    /// The same permutation as `PermutationGenerator8::nth_absolute`, in a single lookup.
+    pub fn nth_absolute(&self, idx: u16) -> Option<impl Iterator<Item = u8>> {
+        let nb_elems = self.nb_elems;
+        self.nth_packed(idx)
+            .map(move |packed| (0..nb_elems).map(move |pos| (packed >> (3 * pos)) as u8 & 0b111))
+    }
+
+    /// The packed form of the `idx`-th permutation.
+    pub fn nth_packed(&self, idx: u16) -> Option<u32> {
+        self.packed.get(idx as usize).copied()
+    }
+
+    /// The packed forms of all the permutations, in lexicographic order.
+    pub fn packed(&self) -> &[u32] {
+        &self.packed
+    }
+
+    /// The inverse of `nth_absolute`, in two lookups once `perm` is checked.
+    ///
+    /// Like `PermutationGenerator::rank`, `NotAPermutation` for the empty permutation.
+    pub fn rank(&self, perm: &[u8]) -> PResult<u16> {
+        if perm.len() != self.nb_elems as usize {
+            return Err(PermutationGeneratorError::LengthMismatch);
+        }
+        if self.packed.is_empty() {
+            return Err(PermutationGeneratorError::NotAPermutation);
+        }
+        check_permutation(perm)?;
+        let (prefix, suffix) = split(pack(perm.iter().copied()), Self::nb_prefix(self.nb_elems));
+        Ok(self.prefix_ranks[prefix as usize] + self.suffix_ranks[suffix as usize])
+    }
+
+    /// The prefix takes the larger half, so that each rank table has at most `2^12` entries.
+    #[inline]
+    fn nb_prefix(nb_elems: u8) -> u8 {
+        nb_elems - nb_elems / 2
+    }
+}
+
+#[inline]
+fn pack(perm: impl IntoIterator<Item = u8>) -> u32 {
+    perm.into_iter()
+        .enumerate()
+        .fold(0, |packed, (pos, elem)| packed | (elem as u32) << (3 * pos))
+}
+
+/// The packed first `nb_prefix` elements and the packed others.
+#[inline]
+fn split(packed: u32, nb_prefix: u8) -> (u32, u32) {
+    (
+        packed & ((1 << (3 * nb_prefix)) - 1),
+        packed >> (3 * nb_prefix),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let table = PermutationTable8::new(8).unwrap();
+        assert_eq!(40320, table.nb_permutations());
+        assert_eq!(8, table.nb_elems());
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            PermutationTable8::new(9).unwrap_err()
+        );
+        assert_eq!(0, PermutationTable8::new(0).unwrap().nb_permutations());
+    }
+
+    #[test]
+    fn matches_permutation_generator() {
+        for nb_elems in 0..=8 {
+            let table = PermutationTable8::new(nb_elems).unwrap();
+            let pg = PermutationGenerator8::new(nb_elems).unwrap();
+            assert_eq!(pg.nb_remaining(), table.nb_permutations() as usize);
+            for (idx, perm) in pg.enumerate() {
+                let perm = perm.collect::<Vec<_>>();
+                let from_table = table.nth_absolute(idx as u16).unwrap().collect::<Vec<_>>();
+                assert_eq!(perm, from_table);
+                assert_eq!(Ok(idx as u16), table.rank(&perm));
+            }
+            assert!(table.nth_absolute(table.nb_permutations()).is_none());
+        }
+    }
+
+    #[test]
+    fn nth_packed() {
+        let table = PermutationTable8::new(4).unwrap();
+        assert_eq!(Some(0b000_001_010_011), table.nth_packed(23));
+        assert_eq!(24, table.packed().len());
+    }
+
+    #[test]
+    fn rank() {
+        let table = PermutationTable8::new(4).unwrap();
+        assert_eq!(Ok(13), table.rank(&[2, 0, 3, 1]));
+        assert_eq!(
+            Err(PermutationGeneratorError::LengthMismatch),
+            table.rank(&[2, 0, 3])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            table.rank(&[2, 0, 2, 1])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            PermutationTable8::new(0).unwrap().rank(&[])
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use super::*;
+
+/// Generates tuples of independent permutations, one per component size, under a single rank.
+///
+/// The rank is mixed radix with factorial radices, the first component being the most significant,
+/// so there are `n_0! n_1! ...` tuples, e.g. `5! 3! 7!` for the sizes `[5, 3, 7]`.
+#[derive(Clone, Debug)]
+pub struct ProductPermutationGenerator<R: Rank> {
+    sizes: Vec<u8>,
+    nb_perms: R,
+    next_idx: R,
+}
+
+pub type ProductPermutationGenerator8 = ProductPermutationGenerator<u16>;
+pub type ProductPermutationGenerator16 = ProductPermutationGenerator<u64>;
+pub type ProductPermutationGenerator32 = ProductPermutationGenerator<u128>;
+
+impl<R: Rank> ProductPermutationGenerator<R> {
+    pub fn new(sizes: &[u8]) -> PResult<Self> {
+        Self::nb_perms_of(sizes).map(|nb_perms| Self {
+            sizes: sizes.to_vec(),
+            nb_perms,
+            next_idx: R::ZERO,
+        })
+    }
+
+    pub fn sizes(&self) -> &[u8] {
+        &self.sizes
+    }
+
+    pub fn next_permutation(&mut self) -> Option<Vec<R::Permutation>> {
+        self.nth(R::ZERO)
+    }
+
+    pub fn nth_absolute(sizes: &[u8], idx: R) -> PResult<Option<Vec<R::Permutation>>> {
+        Self::nb_perms_of(sizes).map(|nb_perms| Self::decode(sizes, nb_perms, idx))
+    }
+
+    pub fn nth(&mut self, step: R) -> Option<Vec<R::Permutation>> {
+        let step_result = self.next_idx.saturating_add(step);
+        self.next_idx = step_result.saturating_add(R::ONE);
+        Self::decode(&self.sizes, self.nb_perms, step_result)
+    }
+
+    /// The index of a tuple of permutations, one per component, each of `0..size`.
+    ///
+    /// There are no tuples without components, or with a component of zero elements.
+    pub fn rank(&self, perms: &[&[u8]]) -> PResult<R> {
+        if perms.len() != self.sizes.len() {
+            return Err(PermutationGeneratorError::LengthMismatch);
+        }
+        if self.nb_perms == R::ZERO {
+            return Err(PermutationGeneratorError::NotAPermutation);
+        }
+        self.sizes
+            .iter()
+            .zip(perms)
+            .try_fold(R::ZERO, |rank, (&size, perm)| {
+                if perm.len() != size as usize {
+                    return Err(PermutationGeneratorError::LengthMismatch);
+                }
+                lehmer_rank::<R>(size, perm.iter().copied())
+                    .map(|perm_rank| rank * R::factorial(size) + perm_rank)
+            })
+    }
+
+    /// The number of tuples, regardless of the progress of the generator.
+    pub fn nb_permutations(&self) -> R {
+        self.nb_perms
+    }
+
+    /// Panics if the number of remaining tuples overflows `usize`
+    pub fn nb_remaining(&self) -> usize {
+        if self.next_idx >= self.nb_perms {
+            return 0;
+        }
+        match (self.nb_perms - self.next_idx).to_usize() {
+            Some(nb) => nb,
+            None => panic!("The size of the iterator overflowed usize"),
+        }
+    }
+
+    /// Like `PermutationGenerator`, a component of zero elements, or no component at all, leaves no tuples.
+    #[inline]
+    fn nb_perms_of(sizes: &[u8]) -> PResult<R> {
+        if sizes.is_empty() {
+            return Ok(R::ZERO);
+        }
+        sizes.iter().try_fold(R::ONE, |nb_perms, &size| {
+            if size > R::MAX_ELEMENTS {
+                return Err(PermutationGeneratorError::TooManyElements);
+            }
+            nb_perms
+                .checked_mul(R::factorial(size))
+                .ok_or(PermutationGeneratorError::TooManyElements)
+        })
+    }
+
+    fn decode(sizes: &[u8], nb_perms: R, mut idx: R) -> Option<Vec<R::Permutation>> {
+        if idx >= nb_perms {
+            return None;
+        }
+        let mut perms = sizes
+            .iter()
+            .rev()
+            .map(|&size| {
+                let nb_component_perms = R::factorial(size);
+                let component_idx = idx % nb_component_perms;
+                idx /= nb_component_perms;
+                R::permutation(size, nb_component_perms, component_idx).unwrap()
+            })
+            .collect::<Vec<_>>();
+        perms.reverse();
+        Some(perms)
+    }
+}
+
+impl<R: Rank> Iterator for ProductPermutationGenerator<R> {
+    type Item = Vec<R::Permutation>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_permutation()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let nb_remaining = self.nb_remaining();
+        (nb_remaining, Some(nb_remaining))
+    }
+
+    fn count(self) -> usize {
+        self.nb_remaining()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect<I: Iterator<Item = u8>>(perms: Option<Vec<I>>) -> Vec<Vec<u8>> {
+        perms
+            .unwrap()
+            .into_iter()
+            .map(|perm| perm.collect())
+            .collect()
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            120 * 6 * 5040,
+            ProductPermutationGenerator32::new(&[5, 3, 7])
+                .unwrap()
+                .nb_permutations()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            ProductPermutationGenerator8::new(&[5, 3, 7]).unwrap_err()
+        );
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            ProductPermutationGenerator8::new(&[9]).unwrap_err()
+        );
+        assert_eq!(
+            0,
+            ProductPermutationGenerator8::new(&[3, 0]).unwrap().count()
+        );
+        assert_eq!(0, ProductPermutationGenerator8::new(&[]).unwrap().count());
+    }
+
+    #[test]
+    fn next_permutation() {
+        let mut pg = ProductPermutationGenerator8::new(&[2, 3]).unwrap();
+        assert_eq!(
+            vec![vec![0, 1], vec![0, 1, 2]],
+            collect(pg.next_permutation())
+        );
+        assert_eq!(
+            vec![vec![0, 1], vec![0, 2, 1]],
+            collect(pg.next_permutation())
+        );
+        assert_eq!(vec![vec![1, 0], vec![0, 1, 2]], collect(pg.nth(4)));
+        assert_eq!(5, pg.nb_remaining());
+        assert_eq!(vec![vec![1, 0], vec![2, 1, 0]], collect(pg.nth(4)));
+        assert!(pg.next_permutation().is_none());
+    }
+
+    #[test]
+    fn nth_absolute() {
+        assert_eq!(
+            vec![
+                vec![4, 3, 2, 1, 0],
+                vec![0, 1, 2],
+                vec![6, 5, 4, 3, 2, 1, 0]
+            ],
+            collect(
+                ProductPermutationGenerator16::nth_absolute(&[5, 3, 7], 119 * 6 * 5040 + 5039)
+                    .unwrap()
+            )
+        );
+        assert!(
+            ProductPermutationGenerator16::nth_absolute(&[5, 3, 7], 120 * 6 * 5040)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn rank() {
+        let pg = ProductPermutationGenerator32::new(&[20, 12, 3]).unwrap();
+        let idx = 1234567890123456789012345678;
+        let perms = collect(pg.clone().nth(idx));
+        let perms = perms.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        assert_eq!(Ok(idx), pg.rank(&perms));
+        assert_eq!(
+            Err(PermutationGeneratorError::LengthMismatch),
+            pg.rank(&perms[..2])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::LengthMismatch),
+            pg.rank(&[perms[0], perms[1], &perms[2][..2]])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            ProductPermutationGenerator8::new(&[2, 2])
+                .unwrap()
+                .rank(&[&[0, 1], &[1, 1]])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            ProductPermutationGenerator8::new(&[0, 2])
+                .unwrap()
+                .rank(&[&[], &[0, 1]])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            ProductPermutationGenerator8::new(&[]).unwrap().rank(&[])
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, Sub, SubAssign};
+
+use super::*;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// The integer type indexing the permutations of one width tier.
+///
+/// `u16` indexes upto 8 elements, `u64` upto 16 elements and `u128` upto 32 elements.
+pub trait Rank:
+    sealed::Sealed
+    + 'static
+    + Copy
+    + Debug
+    + Display
+    + Default
+    + Ord
+    + Hash
+    + From<u8>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign
+    + DivAssign
+{
+    /// The largest number of elements this tier handles.
+    const MAX_ELEMENTS: u8;
+    const ZERO: Self;
+    const ONE: Self;
+    const MAX: Self;
+
+    /// The monomorphised decoder of a single permutation for this tier.
+    type Permutation: Iterator<Item = u8> + Clone + Debug;
+
+    fn factorial(nb_elems: u8) -> Self;
+
+    /// `None` if `idx >= nb_perms`.
+    fn permutation(nb_elems: u8, nb_perms: Self, idx: Self) -> Option<Self::Permutation>;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+
+    fn saturating_add(self, rhs: Self) -> Self;
+
+    /// Truncating conversion, for values known to be smaller than `MAX_ELEMENTS`.
+    fn as_u8(self) -> u8;
+
+    fn to_usize(self) -> Option<usize>;
+
+    fn to_u128(self) -> u128;
+
+    fn from_u128(value: u128) -> Option<Self>;
+}
+
+macro_rules! impl_rank {
+    ($rank_type:ty, $max_elements:expr, $single:ident, $fact:ident) => {
+        impl sealed::Sealed for $rank_type {}
+
+        impl Rank for $rank_type {
+            const MAX_ELEMENTS: u8 = $max_elements;
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MAX: Self = <$rank_type>::MAX;
+
+            type Permutation = $single;
+
+            #[inline]
+            fn factorial(nb_elems: u8) -> Self {
+                $fact(nb_elems)
+            }
+
+            #[inline]
+            fn permutation(nb_elems: u8, nb_perms: Self, idx: Self) -> Option<Self::Permutation> {
+                $single::new(nb_elems, nb_perms, idx)
+            }
+
+            #[inline]
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$rank_type>::checked_add(self, rhs)
+            }
+
+            #[inline]
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$rank_type>::checked_mul(self, rhs)
+            }
+
+            #[inline]
+            fn saturating_add(self, rhs: Self) -> Self {
+                <$rank_type>::saturating_add(self, rhs)
+            }
+
+            #[inline]
+            fn as_u8(self) -> u8 {
+                self as u8
+            }
+
+            #[inline]
+            fn to_usize(self) -> Option<usize> {
+                self.try_into().ok()
+            }
+
+            #[inline]
+            fn to_u128(self) -> u128 {
+                self as u128
+            }
+
+            #[inline]
+            fn from_u128(value: u128) -> Option<Self> {
+                value.try_into().ok()
+            }
+        }
+    };
+}
+
+impl_rank!(u16, 8, SinglePermutation8, factorial16);
+impl_rank!(u64, 16, SinglePermutation16, factorial64);
+impl_rank!(u128, 32, SinglePermutation32, factorial128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn factorial() {
+        assert_eq!(24, <u16 as Rank>::factorial(4));
+        assert_eq!(factorial64(16), <u64 as Rank>::factorial(16));
+        assert_eq!(factorial128(30), <u128 as Rank>::factorial(30));
+        assert_eq!(0, <u16 as Rank>::factorial(0));
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(None, <u16 as Rank>::from_u128(1 << 16));
+        assert_eq!(Some(u16::MAX), <u16 as Rank>::from_u128(u16::MAX as u128));
+        assert_eq!(Some(7), 7u64.to_usize());
+        assert_eq!(3, 259u16.as_u8());
+    }
+}
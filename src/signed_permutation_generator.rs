@@ -0,0 +1,239 @@
+use super::*;
+
+/// Generates the `2^n n!` signed permutations of the hyperoctahedral group.
+///
+/// The rank of a signed permutation is `perm_rank * 2^n + signs`, where `perm_rank` is the lexicographic index of
+/// the unsigned permutation and bit `i` of `signs` is set when the element at position `i` is negative.
+/// `TooManyElements` is returned once `2^n n!` overflows the rank type, e.g. above 6 elements for `u16`.
+#[derive(Clone, Copy, Debug)]
+pub struct SignedPermutationGenerator<R: Rank> {
+    nb_elems: u8,
+    nb_perms: R,
+    next_idx: R,
+}
+
+pub type SignedPermutationGenerator8 = SignedPermutationGenerator<u16>;
+pub type SignedPermutationGenerator16 = SignedPermutationGenerator<u64>;
+pub type SignedPermutationGenerator32 = SignedPermutationGenerator<u128>;
+
+/// A single signed permutation, yielding `(elem, is_negative)` pairs.
+#[derive(Clone, Debug)]
+pub struct SignedPermutation<R: Rank> {
+    perm: R::Permutation,
+    signs: u32,
+}
+
+impl<R: Rank> SignedPermutationGenerator<R> {
+    pub fn new(nb_elems: u8) -> PResult<Self> {
+        Self::nb_signed_perms(nb_elems).map(|nb_perms| Self {
+            nb_elems,
+            nb_perms,
+            next_idx: R::ZERO,
+        })
+    }
+
+    pub fn next_permutation(&mut self) -> Option<SignedPermutation<R>> {
+        self.nth(R::ZERO)
+    }
+
+    pub fn nth_absolute(nb_elems: u8, idx: R) -> PResult<Option<SignedPermutation<R>>> {
+        Self::nb_signed_perms(nb_elems)
+            .map(|nb_perms| SignedPermutation::new(nb_elems, nb_perms, idx))
+    }
+
+    pub fn nth(&mut self, step: R) -> Option<SignedPermutation<R>> {
+        let step_result = self.next_idx.saturating_add(step);
+        let res = SignedPermutation::new(self.nb_elems, self.nb_perms, step_result);
+        self.next_idx = step_result.saturating_add(R::ONE);
+        res
+    }
+
+    /// The index of a signed permutation given as `(elem, is_negative)` pairs, none for zero elements.
+    pub fn rank(perm: &[(u8, bool)]) -> PResult<R> {
+        let nb_elems = u8::try_from(perm.len()).unwrap_or(u8::MAX);
+        if Self::nb_signed_perms(nb_elems)? == R::ZERO {
+            return Err(PermutationGeneratorError::NotAPermutation);
+        }
+        let perm_rank = lehmer_rank::<R>(nb_elems, perm.iter().map(|&(elem, _)| elem))?;
+        let signs = perm.iter().rev().fold(0u32, |signs, &(_, is_negative)| {
+            signs << 1 | is_negative as u32
+        });
+        Ok(perm_rank * Self::nb_sign_patterns(nb_elems) + R::from_u128(signs as u128).unwrap())
+    }
+
+    /// The index of a signed permutation of `1..=n` given as `±(elem + 1)`, see `SignedPermutation::as_i8`.
+    pub fn rank_i8(perm: &[i8]) -> PResult<R> {
+        let mut pairs = [(0, false); 32];
+        if perm.len() > pairs.len() {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        for (pair, &elem) in pairs.iter_mut().zip(perm) {
+            if elem == 0 {
+                return Err(PermutationGeneratorError::NotAPermutation);
+            }
+            *pair = ((elem.unsigned_abs() - 1), elem < 0);
+        }
+        Self::rank(&pairs[..perm.len()])
+    }
+
+    /// Panics if the number of remaining permutations overflows `usize`
+    pub fn nb_remaining(&self) -> usize {
+        if self.next_idx >= self.nb_perms {
+            return 0;
+        }
+        match (self.nb_perms - self.next_idx).to_usize() {
+            Some(nb) => nb,
+            None => panic!("The size of the iterator overflowed usize"),
+        }
+    }
+
+    #[inline]
+    fn nb_sign_patterns(nb_elems: u8) -> R {
+        R::from_u128(1 << nb_elems).unwrap()
+    }
+
+    #[inline]
+    fn nb_signed_perms(nb_elems: u8) -> PResult<R> {
+        if nb_elems > R::MAX_ELEMENTS {
+            return Err(PermutationGeneratorError::TooManyElements);
+        }
+        R::from_u128(1 << nb_elems)
+            .and_then(|nb_sign_patterns| R::factorial(nb_elems).checked_mul(nb_sign_patterns))
+            .ok_or(PermutationGeneratorError::TooManyElements)
+    }
+}
+
+impl<R: Rank> SignedPermutation<R> {
+    fn new(nb_elems: u8, nb_signed_perms: R, idx: R) -> Option<Self> {
+        if idx >= nb_signed_perms {
+            return None;
+        }
+        let nb_sign_patterns = SignedPermutationGenerator::<R>::nb_sign_patterns(nb_elems);
+        R::permutation(nb_elems, R::factorial(nb_elems), idx / nb_sign_patterns).map(|perm| Self {
+            perm,
+            signs: (idx % nb_sign_patterns).to_u128() as u32,
+        })
+    }
+
+    /// Yields `-(elem + 1)` for negative elements and `elem + 1` otherwise, since `0` has no sign.
+    pub fn as_i8(self) -> impl Iterator<Item = i8> {
+        self.map(|(elem, is_negative)| {
+            if is_negative {
+                -(elem as i8 + 1)
+            } else {
+                elem as i8 + 1
+            }
+        })
+    }
+}
+
+impl<R: Rank> Iterator for SignedPermutation<R> {
+    type Item = (u8, bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.perm.next().map(|elem| {
+            let is_negative = self.signs & 1 == 1;
+            self.signs >>= 1;
+            (elem, is_negative)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.perm.size_hint()
+    }
+}
+
+impl<R: Rank> Iterator for SignedPermutationGenerator<R> {
+    type Item = SignedPermutation<R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_permutation()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let nb_remaining = self.nb_remaining();
+        (nb_remaining, Some(nb_remaining))
+    }
+
+    fn count(self) -> usize {
+        self.nb_remaining()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[(u8, bool)], some_iter: Option<SignedPermutation<impl Rank>>) {
+        assert_eq!(ref_slice, some_iter.unwrap().collect::<Vec<_>>().as_slice());
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(46080, SignedPermutationGenerator8::new(6).unwrap().count());
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            SignedPermutationGenerator8::new(7).unwrap_err()
+        );
+        assert!(SignedPermutationGenerator16::new(16).is_ok());
+        assert!(SignedPermutationGenerator32::new(28).is_ok());
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            SignedPermutationGenerator32::new(29).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn next_permutation() {
+        let mut sg = SignedPermutationGenerator8::new(2).unwrap();
+        test_slice(&[(0, false), (1, false)], sg.next_permutation());
+        test_slice(&[(0, true), (1, false)], sg.next_permutation());
+        test_slice(&[(0, false), (1, true)], sg.next_permutation());
+        test_slice(&[(0, true), (1, true)], sg.next_permutation());
+        test_slice(&[(1, false), (0, false)], sg.next_permutation());
+        test_slice(&[(1, true), (0, true)], sg.nth(2));
+        assert!(sg.next_permutation().is_none());
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[(3, true), (2, true), (1, true), (0, true)],
+            SignedPermutationGenerator16::nth_absolute(4, 24 * 16 - 1).unwrap(),
+        );
+        assert_eq!(
+            vec![-2, 1, 3],
+            SignedPermutationGenerator8::nth_absolute(3, 2 * 8 + 1)
+                .unwrap()
+                .unwrap()
+                .as_i8()
+                .collect::<Vec<_>>()
+        );
+        assert!(SignedPermutationGenerator8::nth_absolute(3, 48)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rank() {
+        for (idx, perm) in SignedPermutationGenerator8::new(4).unwrap().enumerate() {
+            let perm = perm.collect::<Vec<_>>();
+            assert_eq!(Ok(idx as u16), SignedPermutationGenerator8::rank(&perm));
+        }
+        let idx = 1234567890123456789012345678901234567;
+        let perm = SignedPermutationGenerator32::nth_absolute(28, idx)
+            .unwrap()
+            .unwrap()
+            .as_i8()
+            .collect::<Vec<_>>();
+        assert_eq!(Ok(idx), SignedPermutationGenerator32::rank_i8(&perm));
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            SignedPermutationGenerator8::rank_i8(&[1, 0, 2])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            SignedPermutationGenerator8::rank(&[])
+        );
+    }
+}
@@ -3,7 +3,9 @@ use super::*;
 mod single_permutation_16;
 mod single_permutation_32;
 mod single_permutation_8;
+mod subset_permutation;
 
 pub(crate) use single_permutation_16::SinglePermutation16;
 pub(crate) use single_permutation_32::SinglePermutation32;
 pub(crate) use single_permutation_8::SinglePermutation8;
+pub(crate) use subset_permutation::SubsetPermutation;
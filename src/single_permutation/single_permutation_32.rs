@@ -1,9 +1,8 @@
 use super::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) struct SinglePermutation32 {
+pub struct SinglePermutation32 {
     elems: BitIndex32,
-    next_mod: u128,
     current_idx: u128,
 }
 
@@ -14,7 +13,6 @@ impl SinglePermutation32 {
         } else {
             Some(Self {
                 elems: BitIndex32::new(nb_elems).unwrap(),
-                next_mod: nb_perms / (nb_elems as u128),
                 current_idx: idx,
             })
         }
@@ -33,9 +31,9 @@ impl Iterator for SinglePermutation32 {
         if self.elems.nb_elements() == 0 {
             return None;
         }
-        let bit_nb = self.current_idx / self.next_mod;
-        self.current_idx -= bit_nb * self.next_mod;
-        self.next_mod /= (self.elems.nb_elements() as u128).saturating_sub(2) + 1;
+        let place = FACTORIALS128[self.elems.nb_elements() as usize - 1];
+        let bit_nb = self.current_idx / place;
+        self.current_idx %= place;
         self.elems.pop(bit_nb as u8)
     }
 
@@ -1,9 +1,8 @@
 use super::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) struct SinglePermutation8 {
+pub struct SinglePermutation8 {
     elems: BitIndex8,
-    next_mod: u16,
     current_idx: u16,
 }
 
@@ -14,7 +13,6 @@ impl SinglePermutation8 {
         } else {
             Some(Self {
                 elems: BitIndex8::new(nb_elems).unwrap(),
-                next_mod: nb_perms / (nb_elems as u16),
                 current_idx: idx,
             })
         }
@@ -33,9 +31,9 @@ impl Iterator for SinglePermutation8 {
         if self.elems.nb_elements() == 0 {
             return None;
         }
-        let bit_nb = self.current_idx / self.next_mod;
-        self.current_idx -= bit_nb * self.next_mod;
-        self.next_mod /= (self.elems.nb_elements() as u16).saturating_sub(2) + 1;
+        let place = FACTORIALS16[self.elems.nb_elements() as usize - 1];
+        let bit_nb = self.current_idx / place;
+        self.current_idx %= place;
         self.elems.pop(bit_nb as u8)
     }
 
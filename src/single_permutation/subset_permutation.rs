@@ -0,0 +1,90 @@
+use super::*;
+
+/// Decodes a single permutation of the labels set in a bitmask, the width tier only sets the rank type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct SubsetPermutation<R: Rank> {
+    elems: BitIndex32,
+    current_idx: R,
+}
+
+impl<R: Rank> SubsetPermutation<R> {
+    pub(crate) fn new(mask: u32, nb_perms: R, idx: R) -> Option<Self> {
+        if idx >= nb_perms {
+            None
+        } else {
+            let mut elems = BitIndex32::empty(32).unwrap();
+            elems.add(mask);
+            Some(Self {
+                elems,
+                current_idx: idx,
+            })
+        }
+    }
+
+    #[inline]
+    fn nb_remaining(&self) -> usize {
+        self.elems.nb_elements() as usize
+    }
+}
+
+impl<R: Rank> Iterator for SubsetPermutation<R> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.elems.nb_elements() == 0 {
+            return None;
+        }
+        let place = nb_arrangements::<R>(self.elems.nb_elements() - 1);
+        let bit_nb = self.current_idx / place;
+        self.current_idx = self.current_idx % place;
+        self.elems.pop(bit_nb.as_u8())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let nb_remaining = self.nb_remaining();
+        (nb_remaining, Some(nb_remaining))
+    }
+
+    fn count(self) -> usize {
+        self.nb_remaining()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subset_perm(mask: u32, idx: u16) -> Option<SubsetPermutation<u16>> {
+        SubsetPermutation::new(mask, factorial16(mask.count_ones() as u8), idx)
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(None, subset_perm(0b1010_0100, 6));
+    }
+
+    #[test]
+    fn new_unchecked_iterator() {
+        assert_eq!(
+            &[2, 5, 7],
+            subset_perm(0b1010_0100, 0)
+                .unwrap()
+                .collect::<Vec<_>>()
+                .as_slice()
+        );
+        assert_eq!(
+            &[5, 2, 7],
+            subset_perm(0b1010_0100, 2)
+                .unwrap()
+                .collect::<Vec<_>>()
+                .as_slice()
+        );
+        assert_eq!(
+            &[31, 11, 0],
+            subset_perm(1 << 31 | 1 << 11 | 1, 5)
+                .unwrap()
+                .collect::<Vec<_>>()
+                .as_slice()
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use super::*;
+
+/// Generates the permutations of an arbitrary subset of the labels `0..32`, given as a bitmask.
+///
+/// Permutations are ordered lexicographically on the labels, ranks only depend on the number of labels.
+#[derive(Clone, Copy, Debug)]
+pub struct SubsetPermutationGenerator<R: Rank> {
+    mask: u32,
+    nb_perms: R,
+    next_idx: R,
+}
+
+pub type SubsetPermutationGenerator8 = SubsetPermutationGenerator<u16>;
+pub type SubsetPermutationGenerator16 = SubsetPermutationGenerator<u64>;
+pub type SubsetPermutationGenerator32 = SubsetPermutationGenerator<u128>;
+
+impl<R: Rank> SubsetPermutationGenerator<R> {
+    /// Permutes the labels `i` for which bit `i` of `mask` is set.
+    pub fn new(mask: u32) -> PResult<Self> {
+        Self::check_mask(mask).map(|nb_elems| Self {
+            mask,
+            nb_perms: R::factorial(nb_elems),
+            next_idx: R::ZERO,
+        })
+    }
+
+    /// Permutes the distinct labels in `elems`, in whatever order they are listed.
+    pub fn from_elements(elems: &[u8]) -> PResult<Self> {
+        Self::mask_of(elems).and_then(Self::new)
+    }
+
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+
+    pub fn next_permutation(&mut self) -> Option<impl Iterator<Item = u8>> {
+        self.nth(R::ZERO)
+    }
+
+    pub fn nth_absolute(mask: u32, idx: R) -> PResult<Option<impl Iterator<Item = u8>>> {
+        Self::check_mask(mask)
+            .map(|nb_elems| SubsetPermutation::new(mask, R::factorial(nb_elems), idx))
+    }
+
+    pub fn nth(&mut self, step: R) -> Option<impl Iterator<Item = u8>> {
+        let step_result = self.next_idx.saturating_add(step);
+        let res = SubsetPermutation::new(self.mask, self.nb_perms, step_result);
+        self.next_idx = step_result.saturating_add(R::ONE);
+        res
+    }
+
+    /// The index of `perm` among the permutations of the labels of this generator, none for an empty mask.
+    pub fn rank(&self, perm: &[u8]) -> PResult<R> {
+        let mut positions = [0; 32];
+        for (position, &label) in positions.iter_mut().zip(perm) {
+            if label >= 32 || self.mask & (1 << label) == 0 {
+                return Err(PermutationGeneratorError::NotAPermutation);
+            }
+            *position = (self.mask & ((1 << label) - 1)).count_ones() as u8;
+        }
+        let nb_elems = self.mask.count_ones() as u8;
+        if perm.len() != nb_elems as usize {
+            return Err(PermutationGeneratorError::LengthMismatch);
+        }
+        if nb_elems == 0 {
+            return Err(PermutationGeneratorError::NotAPermutation);
+        }
+        lehmer_rank(nb_elems, positions[..perm.len()].iter().copied())
+    }
+
+    /// Panics if the number of remaining permutations overflows `usize`, e.g. on more than 20 labels
+    pub fn nb_remaining(&self) -> usize {
+        if self.next_idx >= self.nb_perms {
+            return 0;
+        }
+        match (self.nb_perms - self.next_idx).to_usize() {
+            Some(nb) => nb,
+            None => panic!("The size of the iterator overflowed usize"),
+        }
+    }
+
+    #[inline]
+    fn check_mask(mask: u32) -> PResult<u8> {
+        let nb_elems = mask.count_ones() as u8;
+        if nb_elems > R::MAX_ELEMENTS {
+            Err(PermutationGeneratorError::TooManyElements)
+        } else {
+            Ok(nb_elems)
+        }
+    }
+
+    fn mask_of(elems: &[u8]) -> PResult<u32> {
+        let mut mask = 0u32;
+        for &label in elems {
+            if label >= 32 || mask & (1 << label) != 0 {
+                return Err(PermutationGeneratorError::NotAPermutation);
+            }
+            mask |= 1 << label;
+        }
+        Ok(mask)
+    }
+}
+
+impl<R: Rank> Iterator for SubsetPermutationGenerator<R> {
+    type Item = impl Iterator<Item = u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_permutation()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let nb_remaining = self.nb_remaining();
+        (nb_remaining, Some(nb_remaining))
+    }
+
+    fn count(self) -> usize {
+        self.nb_remaining()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MASK: u32 = 1 << 2 | 1 << 5 | 1 << 7 | 1 << 11;
+
+    fn test_slice(ref_slice: &[u8], some_iter: Option<impl Iterator<Item = u8>>) {
+        assert_eq!(ref_slice, some_iter.unwrap().collect::<Vec<_>>().as_slice());
+    }
+
+    #[test]
+    fn new() {
+        let pg = SubsetPermutationGenerator8::from_elements(&[11, 5, 2, 7]).unwrap();
+        assert_eq!(MASK, pg.mask());
+        assert_eq!(24, pg.nb_remaining());
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            SubsetPermutationGenerator8::new(0x1ff00).unwrap_err()
+        );
+        assert_eq!(
+            PermutationGeneratorError::NotAPermutation,
+            SubsetPermutationGenerator8::from_elements(&[2, 5, 2]).unwrap_err()
+        );
+        assert_eq!(
+            PermutationGeneratorError::NotAPermutation,
+            SubsetPermutationGenerator8::from_elements(&[2, 32]).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn next_permutation() {
+        let mut pg = SubsetPermutationGenerator8::new(MASK).unwrap();
+        test_slice(&[2, 5, 7, 11], pg.next_permutation());
+        test_slice(&[2, 5, 11, 7], pg.next_permutation());
+        test_slice(&[11, 7, 5, 2], pg.nth(21));
+        assert!(pg.next_permutation().is_none());
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[5, 2, 7, 11],
+            SubsetPermutationGenerator16::nth_absolute(MASK, 6).unwrap(),
+        );
+        test_slice(
+            (0..32).rev().collect::<Vec<_>>().as_slice(),
+            SubsetPermutationGenerator32::nth_absolute(u32::MAX, factorial128(32) - 1).unwrap(),
+        );
+        assert!(SubsetPermutationGenerator8::nth_absolute(MASK, 24)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rank() {
+        let pg = SubsetPermutationGenerator8::new(MASK).unwrap();
+        for (idx, perm) in pg.enumerate() {
+            assert_eq!(Ok(idx as u16), pg.rank(&perm.collect::<Vec<_>>()));
+        }
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            pg.rank(&[2, 5, 7, 3])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::LengthMismatch),
+            pg.rank(&[2, 5, 7])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::NotAPermutation),
+            SubsetPermutationGenerator8::new(0).unwrap().rank(&[])
+        );
+    }
+
+    #[test]
+    fn iter() {
+        let pg = SubsetPermutationGenerator16::new(0b1111_1111_1000_0000).unwrap();
+        assert_eq!(factorial64(9) as usize, pg.count());
+    }
+}
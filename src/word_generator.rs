@@ -0,0 +1,236 @@
+use super::*;
+
+/// Generates the `n^k` words of length `k` over the letters `0..n`, repetitions allowed, in lexicographic order.
+///
+/// Like `PermutationGenerator`, there are no words of length zero.
+#[derive(Clone, Copy, Debug)]
+pub struct WordGenerator<R: Rank> {
+    nb_letters: u8,
+    word_len: u8,
+    nb_words: R,
+    next_idx: R,
+}
+
+pub type WordGenerator8 = WordGenerator<u16>;
+pub type WordGenerator16 = WordGenerator<u64>;
+pub type WordGenerator32 = WordGenerator<u128>;
+
+/// A single word, decoded letter by letter from its rank in base `nb_letters`.
+#[derive(Clone, Copy, Debug)]
+pub struct Word<R: Rank> {
+    nb_letters: R,
+    place: R,
+    idx: R,
+    nb_remaining: u8,
+}
+
+impl<R: Rank> WordGenerator<R> {
+    pub fn new(nb_letters: u8, word_len: u8) -> PResult<Self> {
+        Self::nb_words_of(nb_letters, word_len).map(|nb_words| Self {
+            nb_letters,
+            word_len,
+            nb_words,
+            next_idx: R::ZERO,
+        })
+    }
+
+    pub fn next_word(&mut self) -> Option<Word<R>> {
+        self.nth(R::ZERO)
+    }
+
+    pub fn nth_absolute(nb_letters: u8, word_len: u8, idx: R) -> PResult<Option<Word<R>>> {
+        Self::nb_words_of(nb_letters, word_len)
+            .map(|nb_words| Word::new(nb_letters, word_len, nb_words, idx))
+    }
+
+    pub fn nth(&mut self, step: R) -> Option<Word<R>> {
+        let step_result = self.next_idx.saturating_add(step);
+        let res = Word::new(self.nb_letters, self.word_len, self.nb_words, step_result);
+        self.next_idx = step_result.saturating_add(R::ONE);
+        res
+    }
+
+    /// The index of `word`, `OutOfRange` if one of its letters is not below `nb_letters`, or for the empty word.
+    pub fn rank(&self, word: &[u8]) -> PResult<R> {
+        if word.len() != self.word_len as usize {
+            return Err(PermutationGeneratorError::LengthMismatch);
+        }
+        if self.nb_words == R::ZERO {
+            return Err(PermutationGeneratorError::OutOfRange);
+        }
+        word.iter().try_fold(R::ZERO, |rank, &letter| {
+            if letter >= self.nb_letters {
+                return Err(PermutationGeneratorError::OutOfRange);
+            }
+            Ok(rank * R::from(self.nb_letters) + R::from(letter))
+        })
+    }
+
+    /// The number of words, regardless of the progress of the generator.
+    pub fn nb_words(&self) -> R {
+        self.nb_words
+    }
+
+    /// Panics if the number of remaining words overflows `usize`
+    pub fn nb_remaining(&self) -> usize {
+        if self.next_idx >= self.nb_words {
+            return 0;
+        }
+        match (self.nb_words - self.next_idx).to_usize() {
+            Some(nb) => nb,
+            None => panic!("The size of the iterator overflowed usize"),
+        }
+    }
+
+    /// `nb_letters^word_len`, `TooManyElements` if it overflows `R`.
+    #[inline]
+    fn nb_words_of(nb_letters: u8, word_len: u8) -> PResult<R> {
+        if word_len == 0 {
+            return Ok(R::ZERO);
+        }
+        (0..word_len)
+            .try_fold(R::ONE, |nb_words, _| {
+                nb_words.checked_mul(R::from(nb_letters))
+            })
+            .ok_or(PermutationGeneratorError::TooManyElements)
+    }
+}
+
+impl<R: Rank> Word<R> {
+    fn new(nb_letters: u8, word_len: u8, nb_words: R, idx: R) -> Option<Self> {
+        if idx >= nb_words {
+            return None;
+        }
+        let nb_letters = R::from(nb_letters);
+        Some(Self {
+            nb_letters,
+            place: nb_words / nb_letters,
+            idx,
+            nb_remaining: word_len,
+        })
+    }
+}
+
+impl<R: Rank> Iterator for Word<R> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.nb_remaining == 0 {
+            return None;
+        }
+        let letter = (self.idx / self.place).as_u8();
+        self.idx = self.idx % self.place;
+        self.nb_remaining -= 1;
+        if self.nb_remaining > 0 {
+            self.place /= self.nb_letters;
+        }
+        Some(letter)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let nb_remaining = self.nb_remaining as usize;
+        (nb_remaining, Some(nb_remaining))
+    }
+}
+
+impl<R: Rank> ExactSizeIterator for Word<R> {}
+
+impl<R: Rank> Iterator for WordGenerator<R> {
+    type Item = Word<R>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_word()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let nb_remaining = self.nb_remaining();
+        (nb_remaining, Some(nb_remaining))
+    }
+
+    fn count(self) -> usize {
+        self.nb_remaining()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_slice(ref_slice: &[u8], some_word: Option<Word<impl Rank>>) {
+        assert_eq!(ref_slice, some_word.unwrap().collect::<Vec<_>>().as_slice());
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(59049, WordGenerator8::new(3, 10).unwrap().count());
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            WordGenerator8::new(2, 16).unwrap_err()
+        );
+        assert_eq!(1 << 63, WordGenerator16::new(2, 63).unwrap().nb_words());
+        assert_eq!(
+            PermutationGeneratorError::TooManyElements,
+            WordGenerator32::new(255, 17).unwrap_err()
+        );
+        assert_eq!(1, WordGenerator8::new(1, 255).unwrap().count());
+        assert_eq!(0, WordGenerator8::new(0, 3).unwrap().count());
+        assert_eq!(0, WordGenerator8::new(3, 0).unwrap().count());
+    }
+
+    #[test]
+    fn next_word() {
+        let mut wg = WordGenerator8::new(3, 2).unwrap();
+        test_slice(&[0, 0], wg.next_word());
+        test_slice(&[0, 1], wg.next_word());
+        test_slice(&[0, 2], wg.next_word());
+        test_slice(&[1, 0], wg.next_word());
+        test_slice(&[2, 2], wg.nth(4));
+        assert!(wg.next_word().is_none());
+        assert_eq!(0, wg.nb_remaining());
+    }
+
+    #[test]
+    fn nth_absolute() {
+        test_slice(
+            &[1, 0, 1, 1],
+            WordGenerator8::nth_absolute(2, 4, 11).unwrap(),
+        );
+        test_slice(
+            &[254; 16],
+            WordGenerator32::nth_absolute(255, 16, 255u128.pow(16) - 1).unwrap(),
+        );
+        assert!(WordGenerator16::nth_absolute(10, 19, 10u64.pow(19))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn rank() {
+        let wg = WordGenerator16::new(7, 20).unwrap();
+        let idx = 12345678901234567;
+        let word = WordGenerator16::nth_absolute(7, 20, idx)
+            .unwrap()
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(Ok(idx), wg.rank(&word));
+        for (idx, word) in WordGenerator8::new(4, 3).unwrap().enumerate() {
+            let word = word.collect::<Vec<_>>();
+            assert_eq!(
+                Ok(idx as u16),
+                WordGenerator8::new(4, 3).unwrap().rank(&word)
+            );
+        }
+        assert_eq!(
+            Err(PermutationGeneratorError::OutOfRange),
+            wg.rank(&[7; 20])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::LengthMismatch),
+            wg.rank(&[0; 19])
+        );
+        assert_eq!(
+            Err(PermutationGeneratorError::OutOfRange),
+            WordGenerator8::new(4, 0).unwrap().rank(&[])
+        );
+    }
+}